@@ -1,4 +1,5 @@
-use std::collections::{HashMap, VecDeque};
+use std::cmp::max;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
 #[derive(Debug, PartialEq)]
 pub enum MarkNumberResult {
@@ -7,21 +8,46 @@ pub enum MarkNumberResult {
     GameWon,
 }
 
+/// Boards by id, ordered the way they appear in the input.
+pub type BingoBoards = BTreeMap<u32, BingoBoard>;
+
+/// A board wins as soon as one of its patterns is fully marked.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WinPattern {
+    Row,
+    Column,
+    /// Either of the two diagonals, only on square boards.
+    Diagonal,
+    FourCorners,
+    FullCard,
+    /// All of the given `(x, y)` fields.
+    Custom(Vec<(usize, usize)>),
+}
+
 #[derive(Clone)]
-pub struct BingoBoard<const N: usize> {
+pub struct BingoBoard {
     fields: HashMap<u32, (usize, usize)>,
-    size: usize,
+    width: usize,
+    height: usize,
+    marked: Vec<Vec<bool>>,
+    marked_count: usize,
     sum_of_all_fields: u32,
     sum_of_all_marked_fields: u32,
-    row_checked_count: [usize; N],
-    column_checked_count: [usize; N],
+    row_checked_count: Vec<usize>,
+    column_checked_count: Vec<usize>,
+    win_patterns: Vec<WinPattern>,
+    has_won: bool,
 }
 
-impl<const N: usize> BingoBoard<N> {
-    pub fn new(board_grid: &str) -> BingoBoard<N> {
-        let rows: Vec<&str> = board_grid.split("\n").collect();
+impl BingoBoard {
+    pub fn new(board_grid: &str) -> BingoBoard {
+        let rows: Vec<&str> = board_grid
+            .split("\n")
+            .filter(|row| !row.trim().is_empty())
+            .collect();
         let mut x;
         let mut y = 0;
+        let mut width = 0;
         let mut sum_of_all_fields = 0;
 
         let mut fields = HashMap::new();
@@ -29,30 +55,48 @@ impl<const N: usize> BingoBoard<N> {
         for row in rows {
             let numbers: Vec<u32> = row
                 .split(" ")
-                .filter(|number| number.len() > 0)
+                .filter(|number| !number.is_empty())
                 .map(|number| number.parse::<u32>().unwrap())
                 .collect();
             x = 0;
 
             for number in numbers {
-                sum_of_all_fields = sum_of_all_fields + number;
+                sum_of_all_fields += number;
                 fields.insert(number, (x, y));
-                x = x + 1;
+                x += 1;
             }
 
-            y = y + 1;
+            width = max(width, x);
+            y += 1;
         }
 
         BingoBoard {
             fields,
+            width,
+            height: y,
+            marked: vec![vec![false; width]; y],
+            marked_count: 0,
             sum_of_all_fields,
             sum_of_all_marked_fields: 0,
-            size: N,
-            row_checked_count: [0; N],
-            column_checked_count: [0; N],
+            row_checked_count: vec![0; y],
+            column_checked_count: vec![0; width],
+            win_patterns: vec![WinPattern::Row, WinPattern::Column],
+            has_won: false,
         }
     }
 
+    pub fn set_win_patterns(&mut self, win_patterns: Vec<WinPattern>) {
+        self.win_patterns = win_patterns;
+    }
+
+    pub fn get_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn has_won(&self) -> bool {
+        self.has_won
+    }
+
     pub fn fields_to_string(&self) -> String {
         let mut out = String::new();
         for (number, (x, y)) in self.fields.iter() {
@@ -63,40 +107,79 @@ impl<const N: usize> BingoBoard<N> {
 
     pub fn mark_number(&mut self, num: u32) -> MarkNumberResult {
         match self.fields.get(&num) {
-            Some((x, y)) => {
-                self.sum_of_all_marked_fields = self.sum_of_all_marked_fields + num;
-                self.column_checked_count[*x] = self.column_checked_count[*x] + 1;
-                self.row_checked_count[*y] = self.row_checked_count[*y] + 1;
-                if self.column_checked_count[*x] == self.size
-                    || self.row_checked_count[*y] == self.size
+            Some((x, y)) if !self.marked[*y][*x] => {
+                let (x, y) = (*x, *y);
+                self.marked[y][x] = true;
+                self.marked_count += 1;
+                self.sum_of_all_marked_fields += num;
+                self.column_checked_count[x] += 1;
+                self.row_checked_count[y] += 1;
+                if self
+                    .win_patterns
+                    .iter()
+                    .any(|pattern| self.is_pattern_complete(pattern, x, y))
                 {
+                    self.has_won = true;
                     return MarkNumberResult::GameWon;
                 }
                 MarkNumberResult::NumberChecked
             }
+            Some(_) => MarkNumberResult::NumberChecked,
             None => MarkNumberResult::NumberDoesNotExist,
         }
     }
 
+    /// Whether marking `(x, y)` completed the pattern.
+    fn is_pattern_complete(&self, pattern: &WinPattern, x: usize, y: usize) -> bool {
+        let (width, height) = (self.width, self.height);
+        match pattern {
+            WinPattern::Row => self.row_checked_count[y] == width,
+            WinPattern::Column => self.column_checked_count[x] == height,
+            WinPattern::Diagonal => {
+                width == height
+                    && ((x == y && (0..width).all(|i| self.marked[i][i]))
+                        || (x + y == width - 1
+                            && (0..width).all(|i| self.marked[i][width - 1 - i])))
+            }
+            WinPattern::FourCorners => {
+                (x == 0 || x == width - 1)
+                    && (y == 0 || y == height - 1)
+                    && self.marked[0][0]
+                    && self.marked[0][width - 1]
+                    && self.marked[height - 1][0]
+                    && self.marked[height - 1][width - 1]
+            }
+            WinPattern::FullCard => self.marked_count == width * height,
+            WinPattern::Custom(pattern_fields) => {
+                pattern_fields.contains(&(x, y))
+                    && pattern_fields
+                        .iter()
+                        .all(|(x, y)| *y < height && *x < width && self.marked[*y][*x])
+            }
+        }
+    }
+
     pub fn get_sum_of_unmarked_fields(&self) -> u32 {
         self.sum_of_all_fields - self.sum_of_all_marked_fields
     }
 }
 
-pub fn parse_board_data<const N: usize>(input: &String) -> (Vec<u32>, HashMap<u32, BingoBoard<N>>) {
+pub fn parse_board_data(input: &String) -> (Vec<u32>, BingoBoards) {
     let mut instructions: VecDeque<&str> = input.split("\n\n").collect();
     let mut id = 0;
     let numbers: Vec<u32> = instructions
         .pop_front()
         .unwrap()
+        .trim()
         .split(",")
         .map(|number| number.parse::<u32>().unwrap())
         .collect();
 
     let bingo_boards = instructions
         .into_iter()
+        .filter(|board_grid| !board_grid.trim().is_empty())
         .map(|board_grid| {
-            id = id + 1;
+            id += 1;
             (id, BingoBoard::new(board_grid))
         })
         .collect();
@@ -111,7 +194,7 @@ mod tests {
     fn parse_board_data_test() {
         match std::fs::read_to_string("./src/test.txt") {
             Ok(text) => {
-                let (numbers, boards) = parse_board_data::<5>(&text);
+                let (numbers, boards) = parse_board_data(&text);
                 assert_eq!(
                     numbers,
                     [
@@ -134,7 +217,7 @@ mod tests {
     21  9 14 16  7
      6 10  3 18  5
      1 12 20 15 19";
-        let mut bingo_board: BingoBoard<5> = BingoBoard::new(board);
+        let mut bingo_board = BingoBoard::new(board);
 
         assert_eq!(bingo_board.mark_number(2), MarkNumberResult::NumberChecked);
         assert_eq!(
@@ -151,7 +234,7 @@ mod tests {
      6 10  3 18  5
      1 12 20 15 19";
         let sum_of_all_numbers = 300;
-        let mut bingo_board: BingoBoard<5> = BingoBoard::new(board);
+        let mut bingo_board = BingoBoard::new(board);
 
         bingo_board.mark_number(22);
         bingo_board.mark_number(8);
@@ -171,7 +254,7 @@ mod tests {
     21  9 14 16  7
      6 10  3 18  5
      1 12 20 15 19";
-        let mut bingo_board: BingoBoard<5> = BingoBoard::new(board);
+        let mut bingo_board = BingoBoard::new(board);
         let sum_of_all_numbers = 300;
         bingo_board.mark_number(1);
         bingo_board.mark_number(12);
@@ -183,4 +266,77 @@ mod tests {
             sum_of_all_numbers - 1 - 12 - 20 - 15 - 19
         )
     }
+
+    #[test]
+    fn bingo_board_size_test() {
+        let board = " 1  2  3
+     4  5  6";
+        let mut bingo_board = BingoBoard::new(board);
+        assert_eq!(bingo_board.get_size(), (3, 2));
+
+        bingo_board.mark_number(2);
+        assert_eq!(bingo_board.mark_number(5), MarkNumberResult::GameWon);
+        assert!(bingo_board.has_won());
+    }
+
+    #[test]
+    fn bingo_win_diagonal_test() {
+        let board = "22 13 17 11  0
+     8  2 23  4 24
+    21  9 14 16  7
+     6 10  3 18  5
+     1 12 20 15 19";
+        let mut bingo_board = BingoBoard::new(board);
+        for number in [22, 2, 14, 18] {
+            assert_eq!(
+                bingo_board.mark_number(number),
+                MarkNumberResult::NumberChecked
+            );
+        }
+        assert_eq!(bingo_board.mark_number(19), MarkNumberResult::NumberChecked);
+
+        let mut bingo_board = BingoBoard::new(board);
+        bingo_board.set_win_patterns(vec![WinPattern::Diagonal]);
+        for number in [0, 4, 14, 10] {
+            assert_eq!(
+                bingo_board.mark_number(number),
+                MarkNumberResult::NumberChecked
+            );
+        }
+        assert_eq!(bingo_board.mark_number(1), MarkNumberResult::GameWon);
+    }
+
+    #[test]
+    fn bingo_win_patterns_test() {
+        let board = "22 13 17 11  0
+     8  2 23  4 24
+    21  9 14 16  7
+     6 10  3 18  5
+     1 12 20 15 19";
+        let mut bingo_board = BingoBoard::new(board);
+        bingo_board.set_win_patterns(vec![WinPattern::FourCorners]);
+        for number in [22, 0, 1, 14] {
+            assert_eq!(
+                bingo_board.mark_number(number),
+                MarkNumberResult::NumberChecked
+            );
+        }
+        assert_eq!(bingo_board.mark_number(19), MarkNumberResult::GameWon);
+
+        let mut bingo_board = BingoBoard::new(board);
+        bingo_board.set_win_patterns(vec![WinPattern::Custom(vec![(1, 1), (3, 3)])]);
+        assert_eq!(bingo_board.mark_number(2), MarkNumberResult::NumberChecked);
+        assert_eq!(bingo_board.mark_number(18), MarkNumberResult::GameWon);
+
+        let mut bingo_board = BingoBoard::new(board);
+        bingo_board.set_win_patterns(vec![WinPattern::FullCard]);
+        for number in 0..24 {
+            assert_eq!(
+                bingo_board.mark_number(number),
+                MarkNumberResult::NumberChecked
+            );
+        }
+        assert_eq!(bingo_board.mark_number(24), MarkNumberResult::GameWon);
+        assert_eq!(bingo_board.get_sum_of_unmarked_fields(), 0);
+    }
 }
@@ -0,0 +1,174 @@
+use crate::bingo_board::{BingoBoards, MarkNumberResult};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BingoWin {
+    pub board_id: u32,
+    pub number: u32,
+    /// Position of `number` in the drawn numbers.
+    pub draw_index: usize,
+    /// Sum of the unmarked fields times `number`.
+    pub score: u32,
+}
+
+/// Plays bingo one drawn number at a time, so numbers can come from any source and the
+/// game can stop as soon as the question at hand is answered.
+///
+/// Boards are kept ordered by id, which is the order they appear in the input. When
+/// several boards win on the same number they are reported in that order, so the first
+/// winner of such a draw is the one with the lowest id and the last one the highest id.
+/// Boards that have won already don't take part in later draws.
+pub struct BingoGame<'a> {
+    bingo_boards: &'a mut BingoBoards,
+    draw_index: usize,
+    timeline: Vec<BingoWin>,
+}
+
+impl<'a> BingoGame<'a> {
+    pub fn new(bingo_boards: &'a mut BingoBoards) -> BingoGame<'a> {
+        BingoGame {
+            bingo_boards,
+            draw_index: 0,
+            timeline: Vec::new(),
+        }
+    }
+
+    /// Marks the number on every board still in the game and returns the boards that
+    /// won with it.
+    pub fn draw(&mut self, number: u32) -> &[BingoWin] {
+        let wins_before = self.timeline.len();
+        for (board_id, board) in self.bingo_boards.iter_mut() {
+            if board.has_won() {
+                continue;
+            }
+            if board.mark_number(number) == MarkNumberResult::GameWon {
+                self.timeline.push(BingoWin {
+                    board_id: *board_id,
+                    number,
+                    draw_index: self.draw_index,
+                    score: board.get_sum_of_unmarked_fields() * number,
+                });
+            }
+        }
+        self.draw_index += 1;
+        &self.timeline[wins_before..]
+    }
+
+    /// Draws numbers until the first board has won. `None` if the numbers run out first.
+    pub fn play_until_first_win(
+        &mut self,
+        numbers: impl IntoIterator<Item = u32>,
+    ) -> Option<BingoWin> {
+        if let Some(win) = self.get_first_win() {
+            return Some(win.clone());
+        }
+        for number in numbers {
+            if let Some(win) = self.draw(number).first() {
+                return Some(win.clone());
+            }
+        }
+        None
+    }
+
+    /// Draws numbers until every board has won. `None` if the numbers run out first, as
+    /// the last winner can't be known then.
+    pub fn play_until_last_win(
+        &mut self,
+        numbers: impl IntoIterator<Item = u32>,
+    ) -> Option<BingoWin> {
+        let mut numbers = numbers.into_iter();
+        while !self.is_finished() {
+            self.draw(numbers.next()?);
+        }
+        self.timeline.last().cloned()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.bingo_boards.values().all(|board| board.has_won())
+    }
+
+    pub fn get_first_win(&self) -> Option<&BingoWin> {
+        self.timeline.first()
+    }
+
+    /// The latest win so far, which is only the last one once `is_finished`.
+    pub fn get_latest_win(&self) -> Option<&BingoWin> {
+        self.timeline.last()
+    }
+
+    pub fn get_timeline(&self) -> &Vec<BingoWin> {
+        &self.timeline
+    }
+
+    pub fn into_timeline(self) -> Vec<BingoWin> {
+        self.timeline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bingo_board::parse_board_data;
+
+    fn read_test_game() -> (Vec<u32>, BingoBoards) {
+        match std::fs::read_to_string("./src/test.txt") {
+            Ok(text) => parse_board_data(&text),
+            Err(err) => {
+                panic!("{:?}", err);
+            }
+        }
+    }
+
+    #[test]
+    fn draw_test() {
+        let (numbers, mut bingo_boards) = read_test_game();
+        let mut game = BingoGame::new(&mut bingo_boards);
+
+        for number in &numbers[..11] {
+            assert_eq!(game.draw(*number), &[]);
+        }
+        assert_eq!(game.draw(24).len(), 1);
+        assert_eq!(game.get_first_win().unwrap().board_id, 3);
+        assert!(!game.is_finished());
+    }
+
+    #[test]
+    fn streamed_draws_test() {
+        let (numbers, mut bingo_boards) = read_test_game();
+        let mut game = BingoGame::new(&mut bingo_boards);
+
+        // The stream never ends, so this only works if the game stops by itself.
+        let mut stream = numbers.clone().into_iter().chain(std::iter::repeat(99));
+        let first = game.play_until_first_win(&mut stream).unwrap();
+        assert_eq!(
+            (first.board_id, first.score, first.draw_index),
+            (3, 4512, 11)
+        );
+
+        let last = game.play_until_last_win(&mut stream).unwrap();
+        assert_eq!((last.board_id, last.score, last.draw_index), (2, 1924, 14));
+        assert!(game.is_finished());
+    }
+
+    #[test]
+    fn numbers_run_out_test() {
+        let (numbers, mut bingo_boards) = read_test_game();
+        let mut game = BingoGame::new(&mut bingo_boards);
+
+        assert_eq!(game.play_until_last_win(numbers[..12].to_vec()), None);
+        assert_eq!(game.get_latest_win().unwrap().board_id, 3);
+    }
+
+    #[test]
+    fn tie_break_test() {
+        let board = "1 2\n3 4";
+        let (_, mut bingo_boards) =
+            parse_board_data(&format!("1,2\n\n{}\n\n{}\n\n{}", board, board, "5 6\n7 8"));
+        let mut game = BingoGame::new(&mut bingo_boards);
+
+        assert_eq!(game.play_until_first_win(vec![1, 2]).unwrap().board_id, 1);
+        let wins: Vec<u32> = game.get_timeline().iter().map(|w| w.board_id).collect();
+        assert_eq!(wins, vec![1, 2]);
+
+        assert_eq!(game.play_until_last_win(vec![5, 6]).unwrap().board_id, 3);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::bingo_board::{BingoBoard, MarkNumberResult, WinPattern};
+use std::collections::HashSet;
+
+/// Small seeded random number generator (SplitMix64), so generated boards can be
+/// reproduced from the seed alone.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`.
+    pub fn next_below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            values.swap(i, self.next_below(i + 1));
+        }
+    }
+}
+
+fn grid_to_string(width: usize, numbers: &[u32]) -> String {
+    numbers
+        .chunks(width)
+        .map(|row| {
+            row.iter()
+                .map(|number| format!("{:>2}", number))
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// A board grid of distinct numbers from the pool, in the format `BingoBoard::new` reads.
+pub fn generate_board_grid(rng: &mut Rng, width: usize, height: usize, pool: &[u32]) -> String {
+    assert!(
+        pool.len() >= width * height,
+        "not enough numbers in the pool"
+    );
+    let mut numbers = pool.to_vec();
+    rng.shuffle(&mut numbers);
+    numbers.truncate(width * height);
+    grid_to_string(width, &numbers)
+}
+
+/// A whole puzzle input as `parse_board_data` reads it: every number of `0..pool_size`
+/// drawn once in random order, followed by `board_count` boards.
+pub fn generate_input(seed: u64, board_count: usize, size: usize, pool_size: u32) -> String {
+    let mut rng = Rng::new(seed);
+    let pool: Vec<u32> = (0..pool_size).collect();
+
+    let mut numbers = pool.to_vec();
+    rng.shuffle(&mut numbers);
+    let numbers: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+
+    let mut input = numbers.join(",");
+    for _ in 0..board_count {
+        input.push_str("\n\n");
+        input.push_str(&generate_board_grid(&mut rng, size, size, &pool));
+    }
+    input.push('\n');
+    input
+}
+
+/// The fields that have to be marked for the pattern to win, using the fewest fields
+/// the pattern allows. `None` if the pattern can't win on a board of that size.
+fn get_pattern_fields(
+    pattern: &WinPattern,
+    width: usize,
+    height: usize,
+) -> Option<Vec<(usize, usize)>> {
+    match pattern {
+        WinPattern::Row => Some((0..width).map(|x| (x, 0)).collect()),
+        WinPattern::Column => Some((0..height).map(|y| (0, y)).collect()),
+        WinPattern::Diagonal if width == height => Some((0..width).map(|i| (i, i)).collect()),
+        WinPattern::Diagonal => None,
+        WinPattern::FourCorners => {
+            let mut corners = vec![
+                (0, 0),
+                (width - 1, 0),
+                (0, height - 1),
+                (width - 1, height - 1),
+            ];
+            corners.sort();
+            corners.dedup();
+            Some(corners)
+        }
+        WinPattern::FullCard => Some(
+            (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .collect(),
+        ),
+        WinPattern::Custom(fields) => {
+            let mut fields = fields.clone();
+            fields.sort();
+            fields.dedup();
+            match fields.iter().all(|(x, y)| *x < width && *y < height) && !fields.is_empty() {
+                true => Some(fields),
+                false => None,
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BestBoard {
+    /// Draw at which the board wins.
+    pub draw_index: usize,
+    pub board_grid: String,
+    pub board: BingoBoard,
+}
+
+/// The board, out of every board that can be made from the pool, that wins the earliest
+/// with the given draws. The fields of its smallest win pattern hold the first numbers
+/// drawn, the others the numbers drawn last or not at all, which also gives the highest
+/// score that is possible at that point.
+pub fn find_best_board(
+    numbers: &[u32],
+    pool: &[u32],
+    width: usize,
+    height: usize,
+    win_patterns: &[WinPattern],
+) -> Option<BestBoard> {
+    let pool_set: HashSet<u32> = pool.iter().cloned().collect();
+    if pool_set.len() < width * height {
+        return None;
+    }
+
+    let pattern_fields = win_patterns
+        .iter()
+        .filter_map(|pattern| get_pattern_fields(pattern, width, height))
+        .min_by_key(|fields| fields.len())?;
+
+    let mut drawn: Vec<u32> = Vec::new();
+    let mut draw_index = None;
+    for (i, number) in numbers.iter().enumerate() {
+        if pool_set.contains(number) && !drawn.contains(number) {
+            drawn.push(*number);
+            if drawn.len() == pattern_fields.len() && draw_index.is_none() {
+                draw_index = Some(i);
+            }
+        }
+    }
+    let draw_index = draw_index?;
+
+    // Undrawn numbers first, the largest of them first, then the ones drawn last.
+    let mut undrawn: Vec<u32> = pool_set
+        .iter()
+        .filter(|number| !drawn.contains(number))
+        .cloned()
+        .collect();
+    undrawn.sort_by(|a, b| b.cmp(a));
+    let mut filler = undrawn
+        .into_iter()
+        .chain(drawn[pattern_fields.len()..].iter().rev().cloned());
+
+    let mut grid = vec![0; width * height];
+    let mut is_pattern_field = vec![false; width * height];
+    for ((x, y), number) in pattern_fields.iter().zip(&drawn) {
+        grid[y * width + x] = *number;
+        is_pattern_field[y * width + x] = true;
+    }
+    for i in 0..grid.len() {
+        if !is_pattern_field[i] {
+            grid[i] = filler.next().unwrap();
+        }
+    }
+
+    let board_grid = grid_to_string(width, &grid);
+    let mut board = BingoBoard::new(&board_grid);
+    board.set_win_patterns(win_patterns.to_vec());
+    Some(BestBoard {
+        draw_index,
+        board_grid,
+        board,
+    })
+}
+
+/// Index of the draw the board wins with, by marking the numbers on a copy of it.
+pub fn get_win_draw_index(board: &BingoBoard, numbers: &[u32]) -> Option<usize> {
+    let mut board = board.clone();
+    numbers
+        .iter()
+        .position(|number| board.mark_number(*number) == MarkNumberResult::GameWon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bingo_board::parse_board_data;
+
+    #[test]
+    fn rng_test() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+        let a: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        let b: Vec<u64> = (0..5).map(|_| b.next_u64()).collect();
+        let c: Vec<u64> = (0..5).map(|_| c.next_u64()).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn generate_input_test() {
+        let input = generate_input(7, 4, 5, 100);
+        assert_eq!(input, generate_input(7, 4, 5, 100));
+        assert_ne!(input, generate_input(8, 4, 5, 100));
+
+        let (numbers, boards) = parse_board_data(&input);
+        assert_eq!(numbers.len(), 100);
+        assert_eq!(numbers.iter().cloned().collect::<HashSet<u32>>().len(), 100);
+        assert_eq!(boards.len(), 4);
+        for board in boards.values() {
+            assert_eq!(board.get_size(), (5, 5));
+            assert!(get_win_draw_index(board, &numbers).is_some());
+        }
+    }
+
+    #[test]
+    fn find_best_board_test() {
+        match std::fs::read_to_string("./src/test.txt") {
+            Ok(text) => {
+                let (numbers, boards) = parse_board_data(&text);
+                let pool: Vec<u32> = (0..100).collect();
+                let patterns = vec![WinPattern::Row, WinPattern::Column];
+
+                let best = find_best_board(&numbers, &pool, 5, 5, &patterns).unwrap();
+                assert_eq!(best.draw_index, 4);
+                assert_eq!(get_win_draw_index(&best.board, &numbers), Some(4));
+                assert!(best
+                    .board_grid
+                    .starts_with(" 7  4  9  5 11\n99 98 97 96 95"));
+                for board in boards.values() {
+                    assert!(get_win_draw_index(board, &numbers).unwrap() >= best.draw_index);
+                }
+
+                let best =
+                    find_best_board(&numbers, &pool, 5, 5, &[WinPattern::FourCorners]).unwrap();
+                assert_eq!(best.draw_index, 3);
+                assert_eq!(get_win_draw_index(&best.board, &numbers), Some(3));
+            }
+            Err(err) => {
+                panic!("{:?}", err);
+            }
+        }
+    }
+
+    #[test]
+    fn find_best_board_impossible_test() {
+        let numbers = vec![1, 2, 3];
+        let pool: Vec<u32> = (0..25).collect();
+        assert!(find_best_board(&numbers, &pool, 5, 5, &[WinPattern::Row]).is_none());
+        assert!(find_best_board(&numbers, &pool, 6, 5, &[WinPattern::Row]).is_none());
+        assert!(find_best_board(&numbers, &pool, 3, 2, &[WinPattern::Diagonal]).is_none());
+    }
+}
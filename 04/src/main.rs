@@ -1,10 +1,11 @@
-#![feature(drain_filter)]
 #![feature(test)]
 
-use crate::bingo_board::{parse_board_data, BingoBoard, MarkNumberResult};
-use std::collections::HashMap;
+use crate::bingo_board::{parse_board_data, BingoBoards};
+use crate::bingo_game::{BingoGame, BingoWin};
 
 pub mod bingo_board;
+pub mod bingo_game;
+pub mod bingo_generator;
 
 fn main() {
     match std::fs::read_to_string("./src/04.txt") {
@@ -22,72 +23,104 @@ fn main() {
     }
 }
 
-pub fn part_1(numbers: Vec<u32>, bingo_boards: &mut HashMap<u32, BingoBoard<5>>) -> u32 {
-    for number in numbers {
-        for board_id_that_one in play_game(number, bingo_boards) {
-            return bingo_boards
-                .get(&board_id_that_one)
-                .unwrap()
-                .get_sum_of_unmarked_fields()
-                * number;
-        }
+pub fn part_1(numbers: Vec<u32>, bingo_boards: &mut BingoBoards) -> u32 {
+    match BingoGame::new(bingo_boards).play_until_first_win(numbers) {
+        Some(win) => win.score,
+        None => 0,
     }
-    return 0;
 }
 
-pub fn part_2(numbers: Vec<u32>, bingo_boards: &mut HashMap<u32, BingoBoard<5>>) -> u32 {
-    let mut last_sum_some_of_unmarked_fields: u32 = 0;
-    let mut last_number_played: u32 = 0;
-
-    for number in numbers {
-        for board_id_that_one in play_game(number, bingo_boards) {
-            let won_board = bingo_boards.remove(&board_id_that_one).unwrap();
-            last_sum_some_of_unmarked_fields = won_board.get_sum_of_unmarked_fields();
-            last_number_played = number;
-        }
+pub fn part_2(numbers: Vec<u32>, bingo_boards: &mut BingoBoards) -> u32 {
+    match BingoGame::new(bingo_boards).play_until_last_win(numbers) {
+        Some(win) => win.score,
+        None => 0,
     }
-
-    return last_sum_some_of_unmarked_fields * last_number_played;
 }
 
-pub fn play_game<const N: usize>(
-    number: u32,
-    bingo_boards: &mut HashMap<u32, BingoBoard<N>>,
-) -> Vec<u32> {
-    let mut boards_that_won: Vec<u32> = Vec::new();
-    for (board_id, board) in bingo_boards.into_iter() {
-        if board.mark_number(number) == MarkNumberResult::GameWon {
-            boards_that_won.push(*board_id);
-        }
+/// Draws all numbers and lists every board in the order it won, see `BingoGame` for how
+/// boards winning on the same number are ordered.
+pub fn play_game(numbers: &Vec<u32>, bingo_boards: &mut BingoBoards) -> Vec<BingoWin> {
+    let mut game = BingoGame::new(bingo_boards);
+    for number in numbers {
+        game.draw(*number);
     }
-    return boards_that_won;
+    game.into_timeline()
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
-    use crate::bingo_board::parse_board_data;
+    use crate::bingo_board::{parse_board_data, WinPattern};
     use test::Bencher;
 
     #[test]
     pub fn play_test_game() {
         match std::fs::read_to_string("./src/test.txt") {
             Ok(text) => {
-                let (numbers, mut bingo_boards) = parse_board_data::<5>(&text);
-                for number in numbers {
-                    for board_id in play_game(number, &mut bingo_boards) {
-                        assert_eq!(
-                            bingo_boards
-                                .get(&board_id)
-                                .unwrap()
-                                .get_sum_of_unmarked_fields()
-                                * number,
-                            4512
-                        );
+                let (numbers, mut bingo_boards) = parse_board_data(&text);
+                let timeline = play_game(&numbers, &mut bingo_boards);
+                assert_eq!(timeline.len(), 3);
+                assert_eq!(
+                    timeline[0],
+                    BingoWin {
+                        board_id: 3,
+                        number: 24,
+                        draw_index: 11,
+                        score: 4512
                     }
-                    return;
+                );
+                assert_eq!(
+                    timeline[2],
+                    BingoWin {
+                        board_id: 2,
+                        number: 13,
+                        draw_index: 14,
+                        score: 1924
+                    }
+                );
+            }
+            Err(err) => {
+                panic!("{:?}", err);
+            }
+        }
+    }
+
+    #[test]
+    pub fn play_test_game_with_diagonals() {
+        match std::fs::read_to_string("./src/test.txt") {
+            Ok(text) => {
+                let (numbers, mut bingo_boards) = parse_board_data(&text);
+                for board in bingo_boards.values_mut() {
+                    board.set_win_patterns(vec![WinPattern::Diagonal]);
                 }
+                let timeline = play_game(&numbers, &mut bingo_boards);
+                assert_eq!(
+                    timeline,
+                    vec![
+                        // 4, 9, 23, 11, 2 from the top right corner down
+                        BingoWin {
+                            board_id: 3,
+                            number: 2,
+                            draw_index: 7,
+                            score: 247 * 2
+                        },
+                        // 22, 17, 7, 11, 14 from the top right corner down
+                        BingoWin {
+                            board_id: 2,
+                            number: 22,
+                            draw_index: 19,
+                            score: 68 * 22
+                        },
+                        // 22, 2, 14, 18, 19 from the top left corner down
+                        BingoWin {
+                            board_id: 1,
+                            number: 19,
+                            draw_index: 23,
+                            score: 4 * 19
+                        },
+                    ]
+                );
             }
             Err(err) => {
                 panic!("{:?}", err);
@@ -100,7 +133,7 @@ mod tests {
         match std::fs::read_to_string("./src/04.txt") {
             Ok(text) => {
                 b.iter(|| {
-                    let (_, _) = parse_board_data::<5>(&text);
+                    let (_, _) = parse_board_data(&text);
                 });
             }
             Err(err) => {
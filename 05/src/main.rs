@@ -1,124 +1,42 @@
 #![feature(test)]
 
-use std::cmp::{max, min};
+use crate::segment::Segment;
+use crate::sweep::count_overlapping_points;
 
-type ParseOutput = Vec<((usize, usize), (usize, usize))>;
-type Solution = u16;
+pub mod segment;
+pub mod sweep;
+
+type ParseOutput = Vec<Segment>;
+type Solution = u64;
 
 fn main() {
     let parse_output = parse_input_file("src/05.txt");
-    println!(
-        "Solution to part 1 is {}",
-        part_1::<1000>(parse_output.clone())
-    );
-    println!("Solution to part 2 is {}", part_2::<1000>(parse_output));
+    println!("Solution to part 1 is {}", part_1(parse_output.clone()));
+    println!("Solution to part 2 is {}", part_2(parse_output));
 }
 
 fn parse_input_file(input_file: &str) -> ParseOutput {
     let input_file_string = std::fs::read_to_string(input_file).unwrap();
-    let line_descriptions: Vec<&str> = input_file_string.split('\n').collect();
 
-    line_descriptions
-        .into_iter()
+    input_file_string
+        .split('\n')
         .filter(|line| line.len() > 0)
-        .map(|line| {
-            let points = line.split_once(" -> ").unwrap();
-
-            let (x1, y1) = points.0.split_once(",").unwrap();
-            let (x2, y2) = points.1.split_once(",").unwrap();
-
-            (
-                (x1.parse().unwrap(), y1.parse().unwrap()),
-                (x2.parse().unwrap(), y2.parse().unwrap()),
-            )
-        })
+        .map(|line| Segment::parse(line).unwrap())
         .collect()
 }
 
-fn part_1<const N: usize>(parse_output: ParseOutput) -> Solution {
-    let mut grid = vec![[0 as u16; N]; N];
-
-    let straight_lines = filter_straight_lines(parse_output);
-    let mut dangerous_point_count = 0 as u16;
-    for ((x1, y1), (x2, y2)) in straight_lines {
-        let x = x1;
-        let y = y1;
-        if x1 == x2 {
-            let range_y = if y2 > y1 { y1..=y2 } else { y2..=y1 };
-            for y in range_y {
-                grid[x][y] += 1;
-                if grid[x][y] == 2 {
-                    dangerous_point_count += 1;
-                }
-            }
-        } else {
-            let range_x = if x2 > x1 { x1..=x2 } else { x2..=x1 };
-            for x in range_x {
-                grid[x][y] += 1;
-                if grid[x][y] == 2 {
-                    dangerous_point_count += 1;
-                }
-            }
-        }
-    }
-    dangerous_point_count
+fn part_1(parse_output: ParseOutput) -> Solution {
+    count_overlapping_points(&filter_straight_lines(parse_output))
 }
 
-fn part_2<const N: usize>(parse_output: ParseOutput) -> Solution {
-    let mut grid = vec![[0 as u16; N]; N];
-
-    let mut dangerous_point_count = 0 as u16;
-    for ((x1, y1), (x2, y2)) in parse_output {
-        let x = x1;
-        let y = y1;
-        let range_y = if y2 > y1 { y1..=y2 } else { y2..=y1 };
-        let range_x = if x2 > x1 { x1..=x2 } else { x2..=x1 };
-        if x1 == x2 {
-            for y in range_y {
-                grid[x][y] += 1;
-                if grid[x][y] == 2 {
-                    dangerous_point_count += 1;
-                }
-            }
-        } else if y1 == y2 {
-            for x in range_x {
-                grid[x][y] += 1;
-                if grid[x][y] == 2 {
-                    dangerous_point_count += 1;
-                }
-            }
-        } else {
-            let x_min = min(x1, x2);
-            let y_min = min(y1, y2);
-            let y_max = max(y1, y2);
-            let diagonal_distance = y_max - y_min;
-            let slope_is_positive = !((x2 < x1) ^ (y2 < y1));
-
-            if slope_is_positive {
-                for i in 0..diagonal_distance + 1 {
-                    grid[x_min + i][y_min + i] += 1;
-                    if grid[x_min + i][y_min + i] == 2 {
-                        dangerous_point_count += 1;
-                    }
-                }
-            } else {
-                for i in 0..diagonal_distance + 1 {
-                    grid[x_min + i][y_max - i] += 1;
-                    if grid[x_min + i][y_max - i] == 2 {
-                        dangerous_point_count += 1;
-                    }
-                }
-            }
-        }
-    }
-
-    dangerous_point_count
+fn part_2(parse_output: ParseOutput) -> Solution {
+    count_overlapping_points(&parse_output)
 }
 
 fn filter_straight_lines(parse_output: ParseOutput) -> ParseOutput {
     parse_output
         .into_iter()
-        .filter(|((x1, y1), (x2, y2))| x2 ^ x1 == 0 || y2 ^ y1 == 0)
+        .filter(|segment| segment.is_straight())
         .collect()
 }
 
@@ -126,24 +44,33 @@ fn filter_straight_lines(parse_output: ParseOutput) -> ParseOutput {
 mod tests {
     extern crate test;
     use super::*;
+    use crate::segment::OverlapCounter;
     use test::Bencher;
 
+    fn count_overlaps(segments: &ParseOutput) -> OverlapCounter {
+        let mut overlap_counter = OverlapCounter::new();
+        for segment in segments {
+            overlap_counter.add_segment(segment);
+        }
+        overlap_counter
+    }
+
     #[test]
     pub fn bench_pars_should_give_expected_output() {
         let parse_output = parse_input_file("src/test.txt");
         assert_eq!(
             parse_output,
             vec!(
-                ((0, 9), (5, 9)),
-                ((8, 0), (0, 8)),
-                ((9, 4), (3, 4)),
-                ((2, 2), (2, 1)),
-                ((7, 0), (7, 4)),
-                ((6, 4), (2, 0)),
-                ((0, 9), (2, 9)),
-                ((3, 4), (1, 4)),
-                ((0, 0), (8, 8)),
-                ((5, 5), (8, 2)),
+                Segment::new((0, 9), (5, 9)),
+                Segment::new((8, 0), (0, 8)),
+                Segment::new((9, 4), (3, 4)),
+                Segment::new((2, 2), (2, 1)),
+                Segment::new((7, 0), (7, 4)),
+                Segment::new((6, 4), (2, 0)),
+                Segment::new((0, 9), (2, 9)),
+                Segment::new((3, 4), (1, 4)),
+                Segment::new((0, 0), (8, 8)),
+                Segment::new((5, 5), (8, 2)),
             )
         );
     }
@@ -154,12 +81,12 @@ mod tests {
         assert_eq!(
             filter_straight_lines(parse_output),
             vec!(
-                ((0, 9), (5, 9)),
-                ((9, 4), (3, 4)),
-                ((2, 2), (2, 1)),
-                ((7, 0), (7, 4)),
-                ((0, 9), (2, 9)),
-                ((3, 4), (1, 4)),
+                Segment::new((0, 9), (5, 9)),
+                Segment::new((9, 4), (3, 4)),
+                Segment::new((2, 2), (2, 1)),
+                Segment::new((7, 0), (7, 4)),
+                Segment::new((0, 9), (2, 9)),
+                Segment::new((3, 4), (1, 4)),
             )
         );
     }
@@ -167,13 +94,22 @@ mod tests {
     #[test]
     fn test_part_1() {
         let parse_output = parse_input_file("src/test.txt");
-        assert_eq!(part_1::<10>(parse_output), 5);
+        assert_eq!(part_1(parse_output), 5);
     }
 
     #[test]
     fn test_part_2() {
         let parse_output = parse_input_file("src/test.txt");
-        assert_eq!(part_2::<10>(parse_output), 12);
+        assert_eq!(part_2(parse_output), 12);
+    }
+
+    #[test]
+    fn test_overlap_points() {
+        let parse_output = parse_input_file("src/test.txt");
+        assert_eq!(
+            count_overlaps(&filter_straight_lines(parse_output)).get_overlaps(),
+            vec![(0, 9), (1, 9), (2, 9), (3, 4), (7, 4)]
+        );
     }
 
     #[bench]
@@ -187,7 +123,7 @@ mod tests {
     fn bench_part_1(b: &mut Bencher) {
         let parse_output = parse_input_file("src/05.txt");
         b.iter(|| {
-            part_1::<1000>(parse_output.clone());
+            part_1(parse_output.clone());
         });
     }
 
@@ -195,7 +131,7 @@ mod tests {
     fn bench_part_2(b: &mut Bencher) {
         let parse_output = parse_input_file("src/05.txt");
         b.iter(|| {
-            part_2::<1000>(parse_output.clone());
+            part_2(parse_output.clone());
         });
     }
 }
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+pub type Point = (i64, i64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+}
+
+impl Segment {
+    pub fn new(start: Point, end: Point) -> Segment {
+        Segment { start, end }
+    }
+
+    /// Reads a segment written as `x1,y1 -> x2,y2`.
+    pub fn parse(line: &str) -> Result<Segment, String> {
+        let parse_point = |point: &str| -> Result<Point, String> {
+            let (x, y) = point
+                .trim()
+                .split_once(',')
+                .ok_or(format!("expected a point x,y but got `{}`", point))?;
+            Ok((
+                x.trim().parse().map_err(|_| format!("invalid x `{}`", x))?,
+                y.trim().parse().map_err(|_| format!("invalid y `{}`", y))?,
+            ))
+        };
+        let (start, end) = line
+            .split_once("->")
+            .ok_or(format!("expected `x1,y1 -> x2,y2` but got `{}`", line))?;
+        Ok(Segment::new(parse_point(start)?, parse_point(end)?))
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.start.1 == self.end.1
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.start.0 == self.end.0
+    }
+
+    pub fn is_straight(&self) -> bool {
+        self.is_horizontal() || self.is_vertical()
+    }
+
+    /// Exactly 45°, so every point of the segment is on the grid.
+    pub fn is_diagonal(&self) -> bool {
+        (self.end.0 - self.start.0).abs() == (self.end.1 - self.start.1).abs()
+            && !self.is_straight()
+    }
+
+    /// Grid points of the segment from start to end. Straight and 45° segments hit every
+    /// point exactly, other slopes are rasterised with Bresenham's algorithm, which can
+    /// pick different points for a segment and its reverse where the line runs exactly
+    /// between two of them.
+    pub fn points(&self) -> SegmentPoints {
+        let dx = (self.end.0 - self.start.0).abs();
+        let dy = -(self.end.1 - self.start.1).abs();
+        SegmentPoints {
+            current: Some(self.start),
+            end: self.end,
+            dx,
+            dy,
+            step_x: (self.end.0 - self.start.0).signum(),
+            step_y: (self.end.1 - self.start.1).signum(),
+            error: dx + dy,
+        }
+    }
+}
+
+pub struct SegmentPoints {
+    current: Option<Point>,
+    end: Point,
+    dx: i64,
+    dy: i64,
+    step_x: i64,
+    step_y: i64,
+    error: i64,
+}
+
+impl Iterator for SegmentPoints {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        let point = self.current?;
+        if point == self.end {
+            self.current = None;
+            return Some(point);
+        }
+
+        let (mut x, mut y) = point;
+        let doubled_error = 2 * self.error;
+        if doubled_error >= self.dy {
+            self.error += self.dy;
+            x += self.step_x;
+        }
+        if doubled_error <= self.dx {
+            self.error += self.dx;
+            y += self.step_y;
+        }
+        self.current = Some((x, y));
+        Some(point)
+    }
+}
+
+/// Counts how many segments cover each point. Only covered points are stored, so the
+/// counter needs no grid size and takes any coordinates.
+pub struct OverlapCounter {
+    coverage: HashMap<Point, u32>,
+    overlap_count: u64,
+}
+
+impl Default for OverlapCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OverlapCounter {
+    pub fn new() -> OverlapCounter {
+        OverlapCounter {
+            coverage: HashMap::new(),
+            overlap_count: 0,
+        }
+    }
+
+    pub fn add_segment(&mut self, segment: &Segment) {
+        for point in segment.points() {
+            let count = self.coverage.entry(point).or_insert(0);
+            *count += 1;
+            if *count == 2 {
+                self.overlap_count += 1;
+            }
+        }
+    }
+
+    /// Number of points covered by at least two segments.
+    pub fn get_overlap_count(&self) -> u64 {
+        self.overlap_count
+    }
+
+    /// Points covered by at least two segments, ordered by x and then y.
+    pub fn get_overlaps(&self) -> Vec<Point> {
+        let mut overlaps: Vec<Point> = self
+            .coverage
+            .iter()
+            .filter(|(_, count)| **count >= 2)
+            .map(|(point, _)| *point)
+            .collect();
+        overlaps.sort();
+        overlaps
+    }
+
+    pub fn get_coverage(&self, point: &Point) -> u32 {
+        *self.coverage.get(point).unwrap_or(&0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(
+            Segment::parse("-3,4 -> 10,-20"),
+            Ok(Segment::new((-3, 4), (10, -20)))
+        );
+        assert!(Segment::parse("3,4 -> 10").is_err());
+        assert!(Segment::parse("3,4 10,20").is_err());
+    }
+
+    #[test]
+    fn straight_and_diagonal_points_test() {
+        let points: Vec<Point> = Segment::new((2, -1), (-1, -1)).points().collect();
+        assert_eq!(points, vec![(2, -1), (1, -1), (0, -1), (-1, -1)]);
+
+        let points: Vec<Point> = Segment::new((8, 0), (5, 3)).points().collect();
+        assert_eq!(points, vec![(8, 0), (7, 1), (6, 2), (5, 3)]);
+
+        let points: Vec<Point> = Segment::new((1, 1), (1, 1)).points().collect();
+        assert_eq!(points, vec![(1, 1)]);
+    }
+
+    #[test]
+    fn bresenham_points_test() {
+        let points: Vec<Point> = Segment::new((0, 0), (6, 2)).points().collect();
+        assert_eq!(
+            points,
+            vec![(0, 0), (1, 0), (2, 1), (3, 1), (4, 1), (5, 2), (6, 2)]
+        );
+
+        let mut reversed: Vec<Point> = Segment::new((6, 2), (0, 0)).points().collect();
+        reversed.reverse();
+        assert_eq!(reversed.len(), points.len());
+        assert_eq!(reversed.first(), points.first());
+        assert_eq!(reversed.last(), points.last());
+    }
+
+    #[test]
+    fn overlap_counter_test() {
+        let mut counter = OverlapCounter::new();
+        counter.add_segment(&Segment::new((-2, -2), (2, 2)));
+        counter.add_segment(&Segment::new((-2, 2), (2, -2)));
+        counter.add_segment(&Segment::new((-2, 0), (2, 0)));
+
+        assert_eq!(counter.get_overlap_count(), 1);
+        assert_eq!(counter.get_overlaps(), vec![(0, 0)]);
+        assert_eq!(counter.get_coverage(&(0, 0)), 3);
+        assert_eq!(counter.get_coverage(&(1, 0)), 1);
+        assert_eq!(counter.get_coverage(&(5, 5)), 0);
+    }
+}
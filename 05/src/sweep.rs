@@ -0,0 +1,479 @@
+use crate::segment::{Point, Segment};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Fenwick tree over the ranks of the horizontal segments' y coordinates.
+struct Fenwick {
+    tree: Vec<i64>,
+}
+
+impl Fenwick {
+    fn new(size: usize) -> Fenwick {
+        Fenwick {
+            tree: vec![0; size + 1],
+        }
+    }
+
+    fn add(&mut self, rank: usize, value: i64) {
+        let mut i = rank + 1;
+        while i < self.tree.len() {
+            self.tree[i] += value;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Sum over the ranks `0..end`.
+    fn prefix_sum(&self, end: usize) -> i64 {
+        let mut sum = 0;
+        let mut i = end;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+}
+
+/// How many horizontal segments cover each y of the current column.
+struct HorizontalCoverage {
+    ys: Vec<i64>,
+    counts: Vec<u32>,
+    /// Ys covered at least once and at least twice.
+    covered: Fenwick,
+    overlapping: Fenwick,
+    overlap_count: u64,
+}
+
+impl HorizontalCoverage {
+    fn new(ys: Vec<i64>) -> HorizontalCoverage {
+        HorizontalCoverage {
+            counts: vec![0; ys.len()],
+            covered: Fenwick::new(ys.len()),
+            overlapping: Fenwick::new(ys.len()),
+            ys,
+            overlap_count: 0,
+        }
+    }
+
+    fn update(&mut self, y: i64, change: i32) {
+        let rank = self.ys.binary_search(&y).unwrap();
+        let before = self.counts[rank];
+        let after = (before as i32 + change) as u32;
+        self.counts[rank] = after;
+
+        if (before >= 1) != (after >= 1) {
+            self.covered.add(rank, change as i64);
+        }
+        if (before >= 2) != (after >= 2) {
+            self.overlapping.add(rank, change as i64);
+            match change > 0 {
+                true => self.overlap_count += 1,
+                false => self.overlap_count -= 1,
+            }
+        }
+    }
+
+    /// Number of ys in `from..=to` covered by at least one and at least two segments.
+    fn count_in_range(&self, from: i64, to: i64) -> (u64, u64) {
+        let start = self.ys.partition_point(|y| *y < from);
+        let end = self.ys.partition_point(|y| *y <= to);
+        (
+            (self.covered.prefix_sum(end) - self.covered.prefix_sum(start)) as u64,
+            (self.overlapping.prefix_sum(end) - self.overlapping.prefix_sum(start)) as u64,
+        )
+    }
+}
+
+/// Segments that lie on the same lines, each line reduced to the pieces of it that are
+/// covered, together with how many segments cover them.
+struct LineCoverage {
+    /// Per line its covered pieces `(from, to, coverage)`, ordered by position.
+    lines: BTreeMap<i64, Vec<(i64, i64, u32)>>,
+}
+
+impl LineCoverage {
+    /// Takes the segments as `(line, from, to)`.
+    fn new(mut ranges: Vec<(i64, i64, i64)>) -> LineCoverage {
+        ranges.sort_unstable();
+        let mut lines = BTreeMap::new();
+        for ranges in ranges.chunk_by(|a, b| a.0 == b.0) {
+            let mut events: Vec<(i64, i32)> = Vec::with_capacity(ranges.len() * 2);
+            for (_, from, to) in ranges {
+                events.push((*from, 1));
+                events.push((*to + 1, -1));
+            }
+            events.sort_unstable();
+
+            let mut pieces = Vec::new();
+            let mut coverage = 0;
+            for i in 0..events.len() {
+                coverage += events[i].1;
+                match events.get(i + 1) {
+                    Some((next, _)) if *next > events[i].0 && coverage > 0 => {
+                        pieces.push((events[i].0, *next - 1, coverage as u32))
+                    }
+                    _ => {}
+                }
+            }
+            lines.insert(ranges[0].0, pieces);
+        }
+        LineCoverage { lines }
+    }
+
+    fn get_coverage(&self, line: i64, position: i64) -> u32 {
+        let pieces = match self.lines.get(&line) {
+            Some(pieces) => pieces,
+            None => return 0,
+        };
+        match pieces.partition_point(|(from, _, _)| *from <= position) {
+            0 => 0,
+            i if pieces[i - 1].1 >= position => pieces[i - 1].2,
+            _ => 0,
+        }
+    }
+
+    /// Lines in `from..=to` that have a segment on them.
+    fn lines_between(&self, from: i64, to: i64) -> impl Iterator<Item = i64> + '_ {
+        self.lines.range(from..=to).map(|(line, _)| *line)
+    }
+
+    /// Every covered piece as `(line, from, to)`.
+    fn pieces(&self) -> impl Iterator<Item = (i64, i64, i64)> + '_ {
+        self.lines
+            .iter()
+            .flat_map(|(line, pieces)| pieces.iter().map(|(from, to, _)| (*line, *from, *to)))
+    }
+
+    /// Number of points covered by at least two of the segments.
+    fn count_overlapping(&self) -> u64 {
+        self.lines
+            .values()
+            .flatten()
+            .filter(|(_, _, coverage)| *coverage >= 2)
+            .map(|(from, to, _)| (to - from + 1) as u64)
+            .sum()
+    }
+}
+
+/// Counts the points covered by at least two segments.
+///
+/// Horizontal and vertical segments go through `count_straight_overlapping_points`. 45°
+/// segments lie on lines of constant y - x or y + x, so on each of those lines they are
+/// merged into covered pieces like the segments of a single row. Only the points where a
+/// diagonal crosses another segment need to be looked at one by one, which makes a
+/// diagonal cost O(log n) for every row or column of another segment it crosses.
+/// Segments of any other slope are rasterised and cost O(log n) per point.
+pub fn count_overlapping_points(segments: &[Segment]) -> u64 {
+    let (diagonals, others): (Vec<Segment>, Vec<Segment>) =
+        segments.iter().partition(|segment| segment.is_diagonal());
+    count_straight_overlapping_points(&others) + count_diagonal_overlaps(&diagonals, &others)
+}
+
+/// Points that overlap because of the diagonals but aren't overlapped by the other
+/// segments alone.
+fn count_diagonal_overlaps(diagonals: &[Segment], others: &[Segment]) -> u64 {
+    if diagonals.is_empty() {
+        return 0;
+    }
+
+    let mut horizontal = Vec::new();
+    let mut vertical = Vec::new();
+    let mut rasterised: HashMap<Point, u32> = HashMap::new();
+    for segment in others {
+        let ((x1, y1), (x2, y2)) = (segment.start, segment.end);
+        if segment.is_vertical() {
+            vertical.push((x1, y1.min(y2), y1.max(y2)));
+        } else if segment.is_horizontal() {
+            horizontal.push((y1, x1.min(x2), x1.max(x2)));
+        } else {
+            for point in segment.points() {
+                *rasterised.entry(point).or_default() += 1;
+            }
+        }
+    }
+
+    // Rising diagonals are keyed by y - x, falling ones by y + x, both are indexed by x.
+    let mut rising = Vec::new();
+    let mut falling = Vec::new();
+    for segment in diagonals {
+        let ((x1, y1), (x2, y2)) = (segment.start, segment.end);
+        match (x2 - x1).signum() == (y2 - y1).signum() {
+            true => rising.push((y1 - x1, x1.min(x2), x1.max(x2))),
+            false => falling.push((y1 + x1, x1.min(x2), x1.max(x2))),
+        }
+    }
+
+    let horizontal = LineCoverage::new(horizontal);
+    let vertical = LineCoverage::new(vertical);
+    let rising = LineCoverage::new(rising);
+    let falling = LineCoverage::new(falling);
+
+    // Everywhere else a point is only on diagonals of one direction.
+    let mut crossings: Vec<Point> = rasterised.keys().copied().collect();
+    for (c, from, to) in rising.pieces() {
+        crossings.extend(
+            horizontal
+                .lines_between(from + c, to + c)
+                .map(|y| (y - c, y)),
+        );
+        crossings.extend(vertical.lines_between(from, to).map(|x| (x, x + c)));
+        crossings.extend(
+            falling
+                .lines_between(2 * from + c, 2 * to + c)
+                .filter(|d| (d - c).rem_euclid(2) == 0)
+                .map(|d| ((d - c) / 2, (d + c) / 2)),
+        );
+    }
+    for (d, from, to) in falling.pieces() {
+        crossings.extend(
+            horizontal
+                .lines_between(d - to, d - from)
+                .map(|y| (d - y, y)),
+        );
+        crossings.extend(vertical.lines_between(from, to).map(|x| (x, d - x)));
+    }
+    crossings.sort_unstable();
+    crossings.dedup();
+
+    let mut overlap_count = (rising.count_overlapping() + falling.count_overlapping()) as i64;
+    for (x, y) in crossings {
+        let straight = horizontal.get_coverage(y, x)
+            + vertical.get_coverage(x, y)
+            + rasterised.get(&(x, y)).copied().unwrap_or(0);
+        let rising = rising.get_coverage(y - x, x);
+        let falling = falling.get_coverage(y + x, x);
+
+        // Replace what the diagonals on their own and the other segments on their own
+        // counted for this point with what all of them together count.
+        overlap_count += (straight + rising + falling >= 2) as i64
+            - (straight >= 2) as i64
+            - (rising >= 2) as i64
+            - (falling >= 2) as i64;
+    }
+    overlap_count as u64
+}
+
+/// Counts the points covered by at least two segments by sweeping over x.
+///
+/// Horizontal segments only matter where they start and end and vertical segments only at
+/// their own x, so every column between those events looks the same and is counted in
+/// one go. That makes straight segments cost O(log n) each no matter how long they are.
+/// Any other segment is rasterised and costs O(log n) per point.
+fn count_straight_overlapping_points(segments: &[Segment]) -> u64 {
+    let mut horizontal_events: Vec<(i64, i64, i32)> = Vec::new();
+    let mut column_intervals: HashMap<i64, Vec<(i64, i64)>> = HashMap::new();
+    let mut columns: BTreeSet<i64> = BTreeSet::new();
+
+    for segment in segments {
+        let ((x1, y1), (x2, y2)) = (segment.start, segment.end);
+        if segment.is_vertical() {
+            column_intervals
+                .entry(x1)
+                .or_default()
+                .push((y1.min(y2), y1.max(y2)));
+            columns.insert(x1);
+        } else if segment.is_horizontal() {
+            horizontal_events.push((x1.min(x2), y1, 1));
+            horizontal_events.push((x1.max(x2) + 1, y1, -1));
+            columns.insert(x1.min(x2));
+            columns.insert(x1.max(x2) + 1);
+        } else {
+            for (x, y) in segment.points() {
+                column_intervals.entry(x).or_default().push((y, y));
+                columns.insert(x);
+            }
+        }
+    }
+    horizontal_events.sort();
+
+    let mut horizontal_ys: Vec<i64> = horizontal_events.iter().map(|(_, y, _)| *y).collect();
+    horizontal_ys.sort();
+    horizontal_ys.dedup();
+    let mut horizontal_coverage = HorizontalCoverage::new(horizontal_ys);
+
+    let mut overlap_count = 0;
+    let mut next_event = 0;
+    let columns: Vec<i64> = columns.into_iter().collect();
+    for (i, x) in columns.iter().enumerate() {
+        while next_event < horizontal_events.len() && horizontal_events[next_event].0 == *x {
+            let (_, y, change) = horizontal_events[next_event];
+            horizontal_coverage.update(y, change);
+            next_event += 1;
+        }
+
+        overlap_count += horizontal_coverage.overlap_count;
+        if let Some(intervals) = column_intervals.get(x) {
+            overlap_count += count_column_overlaps(intervals, &horizontal_coverage);
+        }
+
+        // The columns up to the next event only have the horizontal segments on them.
+        if let Some(next_x) = columns.get(i + 1) {
+            overlap_count += horizontal_coverage.overlap_count * (next_x - x - 1) as u64;
+        }
+    }
+
+    overlap_count
+}
+
+/// Points of a column that overlap because of the vertical segments and rasterised points
+/// on it but aren't overlapped by the horizontal segments alone.
+fn count_column_overlaps(intervals: &[(i64, i64)], horizontal: &HorizontalCoverage) -> u64 {
+    let mut events: Vec<(i64, i32)> = Vec::with_capacity(intervals.len() * 2);
+    for (from, to) in intervals.iter() {
+        events.push((*from, 1));
+        events.push((*to + 1, -1));
+    }
+    events.sort();
+
+    let mut overlap_count = 0;
+    let mut coverage = 0;
+    for i in 0..events.len() {
+        coverage += events[i].1;
+        let from = events[i].0;
+        let to = match events.get(i + 1) {
+            Some((next, _)) if *next > from => *next - 1,
+            _ => continue,
+        };
+
+        let (covered, overlapping) = horizontal.count_in_range(from, to);
+        overlap_count += match coverage {
+            0 => 0,
+            1 => covered - overlapping,
+            _ => (to - from + 1) as u64 - overlapping,
+        };
+    }
+    overlap_count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segment::OverlapCounter;
+
+    /// Segments with pseudo random ends around the origin, straight, 45° or any slope.
+    fn generate_segments(count: usize, seed: u64) -> Vec<Segment> {
+        let mut state = seed;
+        let mut next = |range: i64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) as i64 % (2 * range + 1)) - range
+        };
+
+        (0..count)
+            .map(|i| {
+                let start = (next(20), next(20));
+                let length = next(15);
+                let end = match i % 5 {
+                    0 => (start.0 + length, start.1),
+                    1 => (start.0, start.1 + length),
+                    2 => (start.0 + length, start.1 + length),
+                    3 => (start.0 + length, start.1 - length),
+                    _ => (next(20), next(20)),
+                };
+                Segment::new(start, end)
+            })
+            .collect()
+    }
+
+    fn count_with_overlap_counter(segments: &[Segment]) -> u64 {
+        let mut overlap_counter = OverlapCounter::new();
+        for segment in segments {
+            overlap_counter.add_segment(segment);
+        }
+        overlap_counter.get_overlap_count()
+    }
+
+    #[test]
+    fn sweep_matches_overlap_counter_test() {
+        for seed in 0..50 {
+            let segments = generate_segments(60, seed);
+            assert_eq!(
+                count_overlapping_points(&segments),
+                count_with_overlap_counter(&segments),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn sweep_edge_cases_test() {
+        assert_eq!(count_overlapping_points(&[]), 0);
+
+        let point = Segment::new((3, 3), (3, 3));
+        assert_eq!(count_overlapping_points(&[point, point]), 1);
+
+        let segments = [
+            Segment::new((0, 0), (9, 0)),
+            Segment::new((9, 0), (0, 0)),
+            Segment::new((4, -5), (4, 5)),
+        ];
+        assert_eq!(count_overlapping_points(&segments), 10);
+    }
+
+    #[test]
+    fn sweep_large_coordinates_test() {
+        let segments: Vec<Segment> = (0..100_000)
+            .map(|i| match i % 2 {
+                0 => Segment::new((-4_000_000, i), (4_000_000, i)),
+                _ => Segment::new((i * 40, -1_000_000), (i * 40, 1_000_000)),
+            })
+            .collect();
+        assert_eq!(count_overlapping_points(&segments), 50_000 * 50_000);
+    }
+
+    #[test]
+    fn sweep_diagonal_edge_cases_test() {
+        let segments = [
+            Segment::new((0, 0), (5, 5)),
+            Segment::new((5, 5), (0, 0)),
+            Segment::new((0, 5), (5, 0)),
+        ];
+        assert_eq!(count_overlapping_points(&segments), 6);
+
+        // The falling diagonals cross between grid points, the rising ones meet at (3, 4).
+        let segments = [
+            Segment::new((0, 0), (3, 3)),
+            Segment::new((0, 1), (1, 0)),
+            Segment::new((2, 3), (3, 4)),
+            Segment::new((3, 4), (4, 5)),
+            Segment::new((3, 4), (3, 10)),
+        ];
+        assert_eq!(count_overlapping_points(&segments), 1);
+    }
+
+    #[test]
+    fn sweep_large_coordinates_with_diagonals_test() {
+        let mut segments: Vec<Segment> = (0..50_000)
+            .flat_map(|j| {
+                [
+                    Segment::new((-4_000_000, 2 * j), (4_000_000, 2 * j)),
+                    Segment::new((80 * j + 40, -4_000_000), (80 * j + 40, 4_000_000)),
+                ]
+            })
+            .collect();
+        for i in 0..20 {
+            // y - x and y + x are odd, so the diagonals never meet a crossing of the
+            // straight segments and only meet each other at grid points.
+            let c = 4 * i + 1;
+            let d = 1_000_000 + 4 * i + 1;
+            segments.push(Segment::new(
+                (-2_000_000, -2_000_000 + c),
+                (2_000_000, 2_000_000 + c),
+            ));
+            segments.push(Segment::new(
+                (-2_000_000, d + 2_000_000),
+                (2_000_000, d - 2_000_000),
+            ));
+        }
+        segments.push(Segment::new((999, 1000), (0, 1)));
+
+        // Every diagonal crosses all 50k rows and the 25k columns with x <= 2M, the 20 by
+        // 20 diagonals cross each other, and the doubled piece adds the 1000 points of
+        // y = x + 1 with 0 <= x <= 999 except the 500 on rows and 12 on columns.
+        assert_eq!(
+            count_overlapping_points(&segments),
+            50_000 * 50_000 + 40 * 75_000 + 20 * 20 + 1000 - 500 - 12
+        );
+    }
+}
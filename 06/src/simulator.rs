@@ -0,0 +1,178 @@
+type Matrix = Vec<Vec<u128>>;
+
+/// Timers a lanternfish goes through. A fish whose timer is 0 resets to `reset_timer` and
+/// spawns a fish with `newborn_timer`, every other fish counts down by one each day.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lifecycle {
+    pub reset_timer: usize,
+    pub newborn_timer: usize,
+}
+
+impl Lifecycle {
+    pub fn new(reset_timer: usize, newborn_timer: usize) -> Lifecycle {
+        Lifecycle {
+            reset_timer,
+            newborn_timer,
+        }
+    }
+
+    /// The lifecycle from the puzzle, 7 days between births and 2 more for newborns.
+    pub fn lanternfish() -> Lifecycle {
+        Lifecycle::new(6, 8)
+    }
+
+    /// One bucket per timer value a fish can have.
+    pub fn get_bucket_count(&self) -> usize {
+        self.reset_timer.max(self.newborn_timer) + 1
+    }
+
+    /// How many fish have each timer value.
+    pub fn get_timer_counts(&self, timers: &[usize]) -> Vec<u128> {
+        let mut timer_counts = vec![0; self.get_bucket_count()];
+        for timer in timers {
+            assert!(
+                *timer < timer_counts.len(),
+                "timer {} is out of the lifecycle",
+                timer
+            );
+            timer_counts[*timer] += 1;
+        }
+        timer_counts
+    }
+
+    /// Entry `[to][from]` is how many fish with timer `to` a single fish with timer `from`
+    /// turns into after one day.
+    fn get_transition_matrix(&self) -> Matrix {
+        let bucket_count = self.get_bucket_count();
+        let mut matrix = vec![vec![0; bucket_count]; bucket_count];
+        for from in 1..bucket_count {
+            matrix[from - 1][from] = 1;
+        }
+        matrix[self.reset_timer][0] += 1;
+        matrix[self.newborn_timer][0] += 1;
+        matrix
+    }
+
+    /// Advances the timer counts one day at a time. `None` if the school outgrows `u128`.
+    pub fn advance_days(&self, timer_counts: &[u128], days: u64) -> Option<Vec<u128>> {
+        let mut timer_counts = timer_counts.to_vec();
+        for _ in 0..days {
+            let spawning = timer_counts.remove(0);
+            timer_counts.push(0);
+            timer_counts[self.reset_timer] =
+                timer_counts[self.reset_timer].checked_add(spawning)?;
+            timer_counts[self.newborn_timer] =
+                timer_counts[self.newborn_timer].checked_add(spawning)?;
+        }
+        Some(timer_counts)
+    }
+
+    /// Jumps to the timer counts after `days` by raising the one day transition to that
+    /// power, which takes O(log days) matrix multiplications. `None` if any number on the
+    /// way outgrows `u128`.
+    pub fn jump_days(&self, timer_counts: &[u128], days: u64) -> Option<Vec<u128>> {
+        let transition = matrix_pow(&self.get_transition_matrix(), days)?;
+        transition
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(timer_counts)
+                    .try_fold(0u128, |sum, (a, b)| sum.checked_add(a.checked_mul(*b)?))
+            })
+            .collect()
+    }
+
+    /// Size of the school after `days`. `None` if it outgrows `u128`.
+    pub fn count_fish(&self, timers: &[usize], days: u64) -> Option<u128> {
+        self.jump_days(&self.get_timer_counts(timers), days)?
+            .into_iter()
+            .try_fold(0u128, |sum, count| sum.checked_add(count))
+    }
+}
+
+fn matrix_mul(a: &Matrix, b: &Matrix) -> Option<Matrix> {
+    let size = a.len();
+    let mut product: Matrix = vec![vec![0; size]; size];
+    for i in 0..size {
+        for k in 0..size {
+            if a[i][k] == 0 {
+                continue;
+            }
+            for j in 0..size {
+                product[i][j] = product[i][j].checked_add(a[i][k].checked_mul(b[k][j])?)?;
+            }
+        }
+    }
+    Some(product)
+}
+
+fn matrix_pow(matrix: &Matrix, mut exponent: u64) -> Option<Matrix> {
+    let size = matrix.len();
+    let mut result: Matrix = (0..size)
+        .map(|i| (0..size).map(|j| (i == j) as u128).collect())
+        .collect();
+    let mut base = matrix.clone();
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = matrix_mul(&result, &base)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = matrix_mul(&base, &base)?;
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jump_matches_advance_test() {
+        let timers = vec![3, 4, 3, 1, 2];
+        for lifecycle in [
+            Lifecycle::lanternfish(),
+            Lifecycle::new(2, 4),
+            Lifecycle::new(5, 5),
+            Lifecycle::new(8, 3),
+        ] {
+            let timer_counts = lifecycle.get_timer_counts(&timers);
+            for days in [0, 1, 7, 18, 80, 200] {
+                assert_eq!(
+                    lifecycle.jump_days(&timer_counts, days),
+                    lifecycle.advance_days(&timer_counts, days),
+                    "{:?} after {} days",
+                    lifecycle,
+                    days
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn count_fish_test() {
+        let timers = vec![3, 4, 3, 1, 2];
+        let lifecycle = Lifecycle::lanternfish();
+        assert_eq!(lifecycle.count_fish(&timers, 18), Some(26));
+        assert_eq!(lifecycle.count_fish(&timers, 80), Some(5934));
+        assert_eq!(lifecycle.count_fish(&timers, 256), Some(26984457539));
+    }
+
+    #[test]
+    fn overflow_test() {
+        let timers = vec![3, 4, 3, 1, 2];
+        let lifecycle = Lifecycle::lanternfish();
+        assert!(lifecycle.count_fish(&timers, 800).is_some());
+        assert_eq!(lifecycle.count_fish(&timers, 1_000_000_000_000), None);
+
+        let timer_counts = lifecycle.get_timer_counts(&timers);
+        assert_eq!(lifecycle.advance_days(&timer_counts, 5000), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn timer_out_of_lifecycle_test() {
+        Lifecycle::new(2, 4).get_timer_counts(&[5]);
+    }
+}
@@ -0,0 +1,254 @@
+/// Fuel a crab burns to move a distance.
+pub trait FuelCost {
+    fn get_cost(&self, distance: usize) -> usize;
+
+    /// Convex costs make the total fuel convex in the target position too, so its minimum
+    /// can be searched for instead of trying every position. Costs have to opt in.
+    fn is_convex(&self) -> bool {
+        false
+    }
+
+    /// Positions the best one is known to be in, if the cost allows to tell from the
+    /// crab positions alone. `positions` is sorted and not empty.
+    fn get_bounds(&self, _positions: &[usize]) -> Option<(usize, usize)> {
+        None
+    }
+
+    fn get_fuel(&self, from: usize, to: usize) -> usize {
+        self.get_cost(from.abs_diff(to))
+    }
+}
+
+fn get_mean_bounds(positions: &[usize], margin: f64) -> (usize, usize) {
+    let mean = positions.iter().sum::<usize>() as f64 / positions.len() as f64;
+    (
+        (mean - margin).floor().max(0.0) as usize,
+        (mean + margin).ceil() as usize,
+    )
+}
+
+/// One fuel per step. The median minimises the sum of distances.
+pub struct Linear;
+
+impl FuelCost for Linear {
+    fn get_cost(&self, distance: usize) -> usize {
+        distance
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    fn get_bounds(&self, positions: &[usize]) -> Option<(usize, usize)> {
+        // The lower median, for an even count every position up to the upper one is as good.
+        let median = positions[(positions.len() - 1) / 2];
+        Some((median, median))
+    }
+}
+
+/// Each step costs one more than the one before, n(n+1)/2 in total. The best position is
+/// less than half a step away from the mean.
+pub struct Triangular;
+
+impl FuelCost for Triangular {
+    fn get_cost(&self, distance: usize) -> usize {
+        distance * (distance + 1) / 2
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    fn get_bounds(&self, positions: &[usize]) -> Option<(usize, usize)> {
+        Some(get_mean_bounds(positions, 0.5))
+    }
+}
+
+/// The square of the distance, which the mean minimises.
+pub struct Quadratic;
+
+impl FuelCost for Quadratic {
+    fn get_cost(&self, distance: usize) -> usize {
+        distance * distance
+    }
+
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    fn get_bounds(&self, positions: &[usize]) -> Option<(usize, usize)> {
+        Some(get_mean_bounds(positions, 0.0))
+    }
+}
+
+/// Any cost function. Only declare it convex if it is, the search relies on it.
+pub struct Custom<F: Fn(usize) -> usize> {
+    pub cost: F,
+    pub convex: bool,
+}
+
+impl<F: Fn(usize) -> usize> FuelCost for Custom<F> {
+    fn get_cost(&self, distance: usize) -> usize {
+        (self.cost)(distance)
+    }
+
+    fn is_convex(&self) -> bool {
+        self.convex
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Alignment {
+    pub position: usize,
+    pub fuel: usize,
+}
+
+pub fn get_total_fuel(positions: &[usize], target: usize, fuel_cost: &dyn FuelCost) -> usize {
+    positions
+        .iter()
+        .map(|position| fuel_cost.get_fuel(*position, target))
+        .sum()
+}
+
+/// The position the crabs align on with the least fuel, the lowest one if several are as
+/// good. Uses the bounds of the cost if it has some, searches the slope of the total fuel
+/// for other convex costs and tries every position otherwise.
+pub fn find_best_alignment(positions: &[usize], fuel_cost: &dyn FuelCost) -> Alignment {
+    assert!(!positions.is_empty(), "there are no crabs to align");
+    let mut positions = positions.to_vec();
+    positions.sort();
+
+    let (mut low, mut high) = (positions[0], positions[positions.len() - 1]);
+    if let Some((bound_low, bound_high)) = fuel_cost.get_bounds(&positions) {
+        low = bound_low.max(low);
+        high = bound_high.min(high);
+    } else if fuel_cost.is_convex() {
+        // Narrows down on the first position the fuel stops falling after.
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if get_total_fuel(&positions, middle, fuel_cost)
+                <= get_total_fuel(&positions, middle + 1, fuel_cost)
+            {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+    }
+
+    (low..=high)
+        .map(|position| Alignment {
+            position,
+            fuel: get_total_fuel(&positions, position, fuel_cost),
+        })
+        .min_by_key(|alignment| alignment.fuel)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input::{parse_main, parse_test};
+
+    fn scan_all_positions(positions: &[usize], fuel_cost: &dyn FuelCost) -> Alignment {
+        let (low, high) = (
+            *positions.iter().min().unwrap(),
+            *positions.iter().max().unwrap(),
+        );
+        (low..=high)
+            .map(|position| Alignment {
+                position,
+                fuel: get_total_fuel(positions, position, fuel_cost),
+            })
+            .min_by_key(|alignment| alignment.fuel)
+            .unwrap()
+    }
+
+    #[test]
+    pub fn test_costs() {
+        assert_eq!(Linear.get_fuel(16, 5), 11);
+        assert_eq!(Triangular.get_fuel(16, 5), 66);
+        assert_eq!(Quadratic.get_fuel(1, 5), 16);
+        let cubic = Custom {
+            cost: |d: usize| d * d * d,
+            convex: true,
+        };
+        assert_eq!(cubic.get_fuel(5, 2), 27);
+    }
+
+    #[test]
+    pub fn test_alignments() {
+        let positions = parse_test();
+        assert_eq!(
+            find_best_alignment(&positions, &Linear),
+            Alignment {
+                position: 2,
+                fuel: 37
+            }
+        );
+        assert_eq!(
+            find_best_alignment(&positions, &Triangular),
+            Alignment {
+                position: 5,
+                fuel: 168
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_optimiser_matches_scan() {
+        let cubic = Custom {
+            cost: |d: usize| d * d * d,
+            convex: true,
+        };
+        let stepped = Custom {
+            cost: |d: usize| (d / 3) * 10 + d % 3,
+            convex: false,
+        };
+        let costs: Vec<&dyn FuelCost> = vec![&Linear, &Triangular, &Quadratic, &cubic, &stepped];
+        for positions in [
+            parse_test(),
+            parse_main(),
+            vec![3],
+            vec![0, 1000],
+            vec![7, 1, 4, 1000],
+        ] {
+            for fuel_cost in &costs {
+                assert_eq!(
+                    find_best_alignment(&positions, *fuel_cost),
+                    scan_all_positions(&positions, *fuel_cost)
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_linear_takes_lower_median() {
+        assert_eq!(
+            find_best_alignment(&[0, 1000], &Linear),
+            Alignment {
+                position: 0,
+                fuel: 1000
+            }
+        );
+        assert_eq!(
+            find_best_alignment(&[9, 2, 5, 1], &Linear),
+            Alignment {
+                position: 2,
+                fuel: 11
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_custom_costs_are_not_convex_by_default() {
+        struct Plain;
+        impl FuelCost for Plain {
+            fn get_cost(&self, distance: usize) -> usize {
+                distance
+            }
+        }
+        assert!(!Plain.is_convex());
+        assert!(Linear.is_convex());
+    }
+}
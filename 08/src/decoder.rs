@@ -0,0 +1,525 @@
+use std::fmt;
+
+/// Set of segments or wires, bit `i` standing for the letter `'a' + i`.
+pub type SegmentMask = u32;
+
+/// More wirings than this are not enumerated, the observations are ambiguous anyway.
+pub const MAX_WIRINGS: usize = 10_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeError {
+    UnknownWire(char),
+    RepeatedWire(char),
+    DuplicateGlyph(String),
+    /// No wiring turns every observed pattern into a glyph.
+    Inconsistent,
+    /// Several wirings fit the observations and they read the output differently.
+    /// `wiring_count` is only a lower bound if the search stopped at its limit.
+    Ambiguous {
+        wiring_count: usize,
+    },
+    /// The search stopped at `limit` wirings, which all read the output the same, but
+    /// there are more that weren't checked.
+    TooManyWirings {
+        limit: usize,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnknownWire(wire) => write!(f, "`{}` is not a wire of the display", wire),
+            DecodeError::RepeatedWire(wire) => write!(f, "wire `{}` is repeated", wire),
+            DecodeError::DuplicateGlyph(label) => {
+                write!(f, "glyph `{}` has the segments of another glyph", label)
+            }
+            DecodeError::Inconsistent => write!(f, "no wiring fits the observed patterns"),
+            DecodeError::Ambiguous { wiring_count } => write!(
+                f,
+                "{} wirings fit the observed patterns and read the output differently",
+                wiring_count
+            ),
+            DecodeError::TooManyWirings { limit } => write!(
+                f,
+                "more than {} wirings fit the observed patterns, not all of them were checked",
+                limit
+            ),
+        }
+    }
+}
+
+/// The segments a display has and which of them light up for each glyph. Segments are
+/// named by letters starting at `a`, glyphs are identified by their index.
+pub struct DisplayDefinition {
+    segment_count: usize,
+    glyphs: Vec<(String, SegmentMask)>,
+}
+
+impl DisplayDefinition {
+    pub fn new(segment_count: usize, glyphs: &[(&str, &str)]) -> Result<Self, DecodeError> {
+        assert!(
+            segment_count <= 26,
+            "segments are named by the letters a to z"
+        );
+        let mut display = DisplayDefinition {
+            segment_count,
+            glyphs: Vec::new(),
+        };
+        for (label, segments) in glyphs {
+            let mask = display.parse_pattern(segments)?;
+            if display.glyphs.iter().any(|(_, other)| *other == mask) {
+                return Err(DecodeError::DuplicateGlyph(label.to_string()));
+            }
+            display.glyphs.push((label.to_string(), mask));
+        }
+        Ok(display)
+    }
+
+    /// The digits 0 to 9 with the segment names of the puzzle:
+    ///
+    ///  aaaa
+    /// b    c
+    ///  dddd
+    /// e    f
+    ///  gggg
+    pub fn seven_segment_digits() -> Self {
+        DisplayDefinition::new(7, &SEVEN_SEGMENT_GLYPHS[..10]).unwrap()
+    }
+
+    /// The digits 0 to 9 followed by A, b, C, d, E and F.
+    pub fn seven_segment_hex() -> Self {
+        DisplayDefinition::new(7, &SEVEN_SEGMENT_GLYPHS).unwrap()
+    }
+
+    /// The digits 0 to 9 followed by the letters A to Z, with the middle bar split in two
+    /// and diagonals and a vertical bar in both halves:
+    ///
+    ///  aaaaaaa
+    /// f i j k b
+    /// f  ijk  b
+    ///  ggg hhh
+    /// e  lmn  c
+    /// e l m n c
+    ///  ddddddd
+    pub fn fourteen_segment() -> Self {
+        DisplayDefinition::new(14, &FOURTEEN_SEGMENT_GLYPHS).unwrap()
+    }
+
+    pub fn get_segment_count(&self) -> usize {
+        self.segment_count
+    }
+
+    pub fn get_glyphs(&self) -> &Vec<(String, SegmentMask)> {
+        &self.glyphs
+    }
+
+    pub fn get_glyph_label(&self, glyph: usize) -> &str {
+        &self.glyphs[glyph].0
+    }
+
+    pub fn find_glyph(&self, segments: SegmentMask) -> Option<usize> {
+        self.glyphs.iter().position(|(_, mask)| *mask == segments)
+    }
+
+    pub fn parse_pattern(&self, pattern: &str) -> Result<SegmentMask, DecodeError> {
+        let mut mask = 0;
+        for wire in pattern.chars() {
+            let index = (wire as u32).wrapping_sub('a' as u32);
+            if index >= self.segment_count as u32 {
+                return Err(DecodeError::UnknownWire(wire));
+            }
+            if mask & (1 << index) != 0 {
+                return Err(DecodeError::RepeatedWire(wire));
+            }
+            mask |= 1 << index;
+        }
+        Ok(mask)
+    }
+
+    pub fn parse_patterns(&self, patterns: &[String]) -> Result<Vec<SegmentMask>, DecodeError> {
+        patterns
+            .iter()
+            .map(|pattern| self.parse_pattern(pattern))
+            .collect()
+    }
+}
+
+const SEVEN_SEGMENT_GLYPHS: [(&str, &str); 16] = [
+    ("0", "abcefg"),
+    ("1", "cf"),
+    ("2", "acdeg"),
+    ("3", "acdfg"),
+    ("4", "bcdf"),
+    ("5", "abdfg"),
+    ("6", "abdefg"),
+    ("7", "acf"),
+    ("8", "abcdefg"),
+    ("9", "abcdfg"),
+    ("A", "abcdef"),
+    ("b", "bdefg"),
+    ("C", "abeg"),
+    ("d", "cdefg"),
+    ("E", "abdeg"),
+    ("F", "abde"),
+];
+
+const FOURTEEN_SEGMENT_GLYPHS: [(&str, &str); 36] = [
+    ("0", "abcdefkl"),
+    ("1", "bck"),
+    ("2", "abdegh"),
+    ("3", "abcdh"),
+    ("4", "bcfgh"),
+    ("5", "adfgn"),
+    ("6", "acdefgh"),
+    ("7", "abc"),
+    ("8", "abcdefgh"),
+    ("9", "abcdfgh"),
+    ("A", "abcefgh"),
+    ("B", "abcdhjm"),
+    ("C", "adef"),
+    ("D", "abcdjm"),
+    ("E", "adefg"),
+    ("F", "aefg"),
+    ("G", "acdefh"),
+    ("H", "bcefgh"),
+    ("I", "adjm"),
+    ("J", "bcde"),
+    ("K", "efgkn"),
+    ("L", "def"),
+    ("M", "bcefik"),
+    ("N", "bcefin"),
+    ("O", "abcdef"),
+    ("P", "abefgh"),
+    ("Q", "abcdefn"),
+    ("R", "abefghn"),
+    ("S", "acdfgh"),
+    ("T", "ajm"),
+    ("U", "bcdef"),
+    ("V", "efkl"),
+    ("W", "bcefln"),
+    ("X", "ikln"),
+    ("Y", "ikm"),
+    ("Z", "adkl"),
+];
+
+/// Which segment each wire is connected to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wiring {
+    segments: Vec<usize>,
+}
+
+impl Wiring {
+    pub fn get_segment(&self, wire: usize) -> usize {
+        self.segments[wire]
+    }
+
+    pub fn apply(&self, wires: SegmentMask) -> SegmentMask {
+        self.segments
+            .iter()
+            .enumerate()
+            .filter(|(wire, _)| wires & (1 << wire) != 0)
+            .fold(0, |mask, (_, segment)| mask | (1 << segment))
+    }
+}
+
+/// The wires in order of the segments they are connected to, `abcdefg` for a display
+/// that isn't scrambled.
+impl fmt::Display for Wiring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut wires: Vec<(usize, char)> = self
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(wire, segment)| (*segment, (b'a' + wire as u8) as char))
+            .collect();
+        wires.sort();
+        let wires: String = wires.into_iter().map(|(_, wire)| wire).collect();
+        write!(f, "{}", wires)
+    }
+}
+
+/// Finds every wiring, up to `limit`, that turns each of the patterns into a glyph of the
+/// display. Wires are connected one after the other and a connection is dropped as soon
+/// as some pattern can't become a glyph with the same number of segments anymore.
+pub fn find_wirings(
+    display: &DisplayDefinition,
+    patterns: &[SegmentMask],
+    limit: usize,
+) -> Vec<Wiring> {
+    let mut patterns = patterns.to_vec();
+    patterns.sort();
+    patterns.dedup();
+
+    let mut search = WiringSearch {
+        display,
+        patterns,
+        segments: Vec::new(),
+        used_segments: 0,
+        wirings: Vec::new(),
+        limit,
+    };
+    search.connect_next_wire();
+    search.wirings
+}
+
+struct WiringSearch<'a> {
+    display: &'a DisplayDefinition,
+    patterns: Vec<SegmentMask>,
+    segments: Vec<usize>,
+    used_segments: SegmentMask,
+    wirings: Vec<Wiring>,
+    limit: usize,
+}
+
+impl<'a> WiringSearch<'a> {
+    fn connect_next_wire(&mut self) {
+        if self.wirings.len() >= self.limit {
+            return;
+        }
+        if self.segments.len() == self.display.segment_count {
+            self.wirings.push(Wiring {
+                segments: self.segments.clone(),
+            });
+            return;
+        }
+
+        for segment in 0..self.display.segment_count {
+            if self.used_segments & (1 << segment) != 0 {
+                continue;
+            }
+            self.segments.push(segment);
+            self.used_segments |= 1 << segment;
+            if self.can_be_completed() {
+                self.connect_next_wire();
+            }
+            self.used_segments &= !(1 << segment);
+            self.segments.pop();
+        }
+    }
+
+    /// Every pattern still has a glyph of its size that contains the segments of its
+    /// connected wires and none of the segments of the connected wires it doesn't use.
+    fn can_be_completed(&self) -> bool {
+        self.patterns.iter().all(|pattern| {
+            let (mut lit, mut unlit) = (0, 0);
+            for (wire, segment) in self.segments.iter().enumerate() {
+                match pattern & (1 << wire) != 0 {
+                    true => lit |= 1 << segment,
+                    false => unlit |= 1 << segment,
+                }
+            }
+            self.display.glyphs.iter().any(|(_, glyph)| {
+                glyph.count_ones() == pattern.count_ones()
+                    && glyph & lit == lit
+                    && glyph & unlit == 0
+            })
+        })
+    }
+}
+
+/// Reads the output patterns of an entry. Every pattern, input and output, is used to
+/// find the wiring. Several wirings are fine as long as they all read the output the same.
+pub fn decode_entry(
+    display: &DisplayDefinition,
+    inputs: &[String],
+    outputs: &[String],
+) -> Result<Vec<usize>, DecodeError> {
+    decode_entry_with_limit(display, inputs, outputs, MAX_WIRINGS)
+}
+
+/// `decode_entry` that gives up once `limit` wirings fit, unless those already read the
+/// output differently.
+pub fn decode_entry_with_limit(
+    display: &DisplayDefinition,
+    inputs: &[String],
+    outputs: &[String],
+    limit: usize,
+) -> Result<Vec<usize>, DecodeError> {
+    let inputs = display.parse_patterns(inputs)?;
+    let outputs = display.parse_patterns(outputs)?;
+
+    let patterns: Vec<SegmentMask> = inputs.iter().chain(&outputs).cloned().collect();
+    let wirings = find_wirings(display, &patterns, limit);
+    let read_output = |wiring: &Wiring| -> Vec<usize> {
+        outputs
+            .iter()
+            .map(|pattern| display.find_glyph(wiring.apply(*pattern)).unwrap())
+            .collect()
+    };
+
+    let output = match wirings.first() {
+        Some(wiring) => read_output(wiring),
+        None => return Err(DecodeError::Inconsistent),
+    };
+    if wirings.iter().any(|wiring| read_output(wiring) != output) {
+        return Err(DecodeError::Ambiguous {
+            wiring_count: wirings.len(),
+        });
+    }
+    match wirings.len() >= limit {
+        true => Err(DecodeError::TooManyWirings { limit }),
+        false => Ok(output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_strings(patterns: &str) -> Vec<String> {
+        patterns.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn display_definition_test() {
+        let display = DisplayDefinition::seven_segment_hex();
+        assert_eq!(display.get_glyphs().len(), 16);
+        assert_eq!(display.parse_pattern("cf"), Ok(0b100100));
+        assert_eq!(display.find_glyph(0b100100), Some(1));
+        assert_eq!(display.get_glyph_label(11), "b");
+        assert_eq!(
+            display.parse_pattern("ah"),
+            Err(DecodeError::UnknownWire('h'))
+        );
+        assert_eq!(
+            display.parse_pattern("aba"),
+            Err(DecodeError::RepeatedWire('a'))
+        );
+
+        assert_eq!(
+            DisplayDefinition::new(3, &[("x", "ab"), ("y", "ba")]).err(),
+            Some(DecodeError::DuplicateGlyph("y".to_string()))
+        );
+    }
+
+    #[test]
+    fn decode_entry_test() {
+        let display = DisplayDefinition::seven_segment_digits();
+        let inputs = to_strings("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab");
+        let wirings = find_wirings(
+            &display,
+            &inputs
+                .iter()
+                .map(|p| display.parse_pattern(p).unwrap())
+                .collect::<Vec<SegmentMask>>(),
+            MAX_WIRINGS,
+        );
+        assert_eq!(wirings.len(), 1);
+        assert_eq!(wirings[0].to_string(), "deafgbc");
+
+        let outputs = to_strings("cdfeb fcadb cdfeb cdbaf");
+        assert_eq!(
+            decode_entry(&display, &inputs, &outputs),
+            Ok(vec![5, 3, 5, 3])
+        );
+    }
+
+    #[test]
+    fn decode_hex_test() {
+        let display = DisplayDefinition::seven_segment_hex();
+        // Every hex glyph seen through the wiring a->g, b->a, c->b, ...
+        let scramble = |segments: &str| -> String {
+            segments
+                .chars()
+                .map(|c| (b'a' + (c as u8 - b'a' + 6) % 7) as char)
+                .collect()
+        };
+        let inputs: Vec<String> = SEVEN_SEGMENT_GLYPHS
+            .iter()
+            .map(|(_, s)| scramble(s))
+            .collect();
+        let outputs = vec![scramble("abcdef"), scramble("abde"), scramble("cf")];
+        assert_eq!(
+            decode_entry(&display, &inputs, &outputs),
+            Ok(vec![10, 15, 1])
+        );
+    }
+
+    #[test]
+    fn decode_custom_display_test() {
+        // Two segments side by side, glyphs for left, right and both.
+        let display = DisplayDefinition::new(2, &[("l", "a"), ("r", "b"), ("lr", "ab")]).unwrap();
+        let both = to_strings("ab");
+        assert_eq!(decode_entry(&display, &both, &both), Ok(vec![2]));
+        assert_eq!(
+            decode_entry(&display, &both, &to_strings("a")),
+            Err(DecodeError::Ambiguous { wiring_count: 2 })
+        );
+        assert_eq!(
+            decode_entry(&display, &to_strings("a b"), &to_strings("ab")),
+            Ok(vec![2])
+        );
+    }
+
+    #[test]
+    fn decode_fourteen_segment_test() {
+        let display = DisplayDefinition::fourteen_segment();
+        assert_eq!(display.get_segment_count(), 14);
+        assert_eq!(display.get_glyphs().len(), 36);
+        assert_eq!(display.get_glyph_label(35), "Z");
+
+        // Every glyph seen through the wiring a->j, b->k, c->l, ...
+        let scramble = |segments: &str| -> String {
+            segments
+                .chars()
+                .map(|c| (b'a' + (c as u8 - b'a' + 9) % 14) as char)
+                .collect()
+        };
+        let inputs: Vec<String> = FOURTEEN_SEGMENT_GLYPHS
+            .iter()
+            .map(|(_, s)| scramble(s))
+            .collect();
+        let wirings = find_wirings(
+            &display,
+            &display.parse_patterns(&inputs).unwrap(),
+            MAX_WIRINGS,
+        );
+        assert_eq!(wirings.len(), 1);
+        assert_eq!(wirings[0].to_string(), "jklmnabcdefghi");
+
+        let outputs = vec![scramble("ikm"), scramble("bck"), scramble("bcefin")];
+        let glyphs = decode_entry(&display, &inputs, &outputs).unwrap();
+        let labels: Vec<&str> = glyphs
+            .iter()
+            .map(|glyph| display.get_glyph_label(*glyph))
+            .collect();
+        assert_eq!(labels, vec!["Y", "1", "N"]);
+    }
+
+    #[test]
+    fn wiring_limit_test() {
+        let display = DisplayDefinition::new(2, &[("l", "a"), ("r", "b"), ("lr", "ab")]).unwrap();
+        // Both wirings read `ab` as both segments, but with a limit of two there could be
+        // more that don't.
+        let (inputs, outputs) = (to_strings("a b"), to_strings("ab"));
+        assert_eq!(
+            decode_entry_with_limit(&display, &inputs, &outputs, 2),
+            Err(DecodeError::TooManyWirings { limit: 2 })
+        );
+        assert_eq!(
+            decode_entry_with_limit(&display, &inputs, &outputs, 3),
+            Ok(vec![2])
+        );
+        assert_eq!(
+            decode_entry_with_limit(&display, &inputs, &to_strings("a"), 2),
+            Err(DecodeError::Ambiguous { wiring_count: 2 })
+        );
+    }
+
+    #[test]
+    fn inconsistent_entry_test() {
+        let display = DisplayDefinition::seven_segment_digits();
+        assert_eq!(
+            decode_entry(&display, &to_strings("ab abc abcd"), &to_strings("ab")),
+            Err(DecodeError::Inconsistent)
+        );
+        assert_eq!(
+            decode_entry(&display, &to_strings("ab ac"), &to_strings("ab")),
+            Err(DecodeError::Inconsistent)
+        );
+        assert_eq!(
+            decode_entry(&display, &to_strings("xy"), &to_strings("ab")),
+            Err(DecodeError::UnknownWire('x'))
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::decoder::{
+    find_wirings, DecodeError, DisplayDefinition, SegmentMask, Wiring, MAX_WIRINGS,
+};
+
+/// What the patterns of an entry tell about its wiring.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntryDiagnostics {
+    /// Wirings that fit every input and output pattern.
+    pub wirings: Vec<Wiring>,
+    /// There were `MAX_WIRINGS` or more and only that many are listed.
+    pub wirings_truncated: bool,
+    /// For each output pattern the glyphs it reads as with any of the wirings.
+    pub output_candidates: Vec<Vec<usize>>,
+    /// A smallest set of patterns, by index, that no wiring fits together. Taking any
+    /// one of them away makes the rest fit. Empty if the entry is consistent.
+    pub conflicting_inputs: Vec<usize>,
+    pub conflicting_outputs: Vec<usize>,
+}
+
+impl EntryDiagnostics {
+    pub fn is_consistent(&self) -> bool {
+        !self.wirings.is_empty()
+    }
+
+    /// Output patterns, by index, that could be more than one glyph.
+    pub fn get_ambiguous_outputs(&self) -> Vec<usize> {
+        self.output_candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, glyphs)| glyphs.len() > 1)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// One line with the wirings and what the outputs read as, using the labels of `display`.
+    pub fn describe(&self, display: &DisplayDefinition) -> String {
+        if !self.is_consistent() {
+            return format!(
+                "inconsistent, inputs {:?} and outputs {:?} contradict each other",
+                self.conflicting_inputs, self.conflicting_outputs
+            );
+        }
+
+        let wirings: Vec<String> = self.wirings.iter().map(Wiring::to_string).collect();
+        let outputs: Vec<String> = self
+            .output_candidates
+            .iter()
+            .map(|glyphs| {
+                let labels: Vec<&str> = glyphs
+                    .iter()
+                    .map(|glyph| display.get_glyph_label(*glyph))
+                    .collect();
+                match labels.len() {
+                    1 => labels[0].to_string(),
+                    _ => format!("[{}]", labels.join("|")),
+                }
+            })
+            .collect();
+        format!(
+            "{}{} wiring(s) {}, output {}",
+            self.wirings.len(),
+            if self.wirings_truncated { "+" } else { "" },
+            wirings.join(" "),
+            outputs.join(" ")
+        )
+    }
+}
+
+fn fits(display: &DisplayDefinition, patterns: &[SegmentMask]) -> bool {
+    !find_wirings(display, patterns, 1).is_empty()
+}
+
+/// Drops patterns one at a time as long as the rest still contradicts itself, so what is
+/// left contradicts itself only as a whole.
+fn find_conflict(display: &DisplayDefinition, patterns: &[SegmentMask]) -> Vec<usize> {
+    let mut conflict: Vec<usize> = (0..patterns.len()).collect();
+    let mut i = 0;
+    while i < conflict.len() {
+        let rest: Vec<SegmentMask> = conflict
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, pattern)| patterns[*pattern])
+            .collect();
+        match fits(display, &rest) {
+            true => i += 1,
+            false => {
+                conflict.remove(i);
+            }
+        }
+    }
+    conflict
+}
+
+/// Like `decode_entry`, but instead of giving up on entries that can't be read it tells
+/// what can be known about them. Only unknown or repeated wires are errors.
+pub fn diagnose_entry(
+    display: &DisplayDefinition,
+    inputs: &[String],
+    outputs: &[String],
+) -> Result<EntryDiagnostics, DecodeError> {
+    let inputs = display.parse_patterns(inputs)?;
+    let outputs = display.parse_patterns(outputs)?;
+    let patterns: Vec<SegmentMask> = inputs.iter().chain(&outputs).cloned().collect();
+
+    let wirings = find_wirings(display, &patterns, MAX_WIRINGS);
+    let output_candidates = outputs
+        .iter()
+        .map(|pattern| {
+            let mut glyphs: Vec<usize> = wirings
+                .iter()
+                .map(|wiring| display.find_glyph(wiring.apply(*pattern)).unwrap())
+                .collect();
+            glyphs.sort();
+            glyphs.dedup();
+            glyphs
+        })
+        .collect();
+
+    let conflict = match wirings.len() {
+        0 => find_conflict(display, &patterns),
+        _ => Vec::new(),
+    };
+
+    Ok(EntryDiagnostics {
+        wirings_truncated: wirings.len() >= MAX_WIRINGS,
+        wirings,
+        output_candidates,
+        conflicting_inputs: conflict
+            .iter()
+            .filter(|i| **i < inputs.len())
+            .cloned()
+            .collect(),
+        conflicting_outputs: conflict
+            .iter()
+            .filter(|i| **i >= inputs.len())
+            .map(|i| i - inputs.len())
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_strings(patterns: &str) -> Vec<String> {
+        patterns.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn complete_entry_test() {
+        let display = DisplayDefinition::seven_segment_digits();
+        let diagnostics = diagnose_entry(
+            &display,
+            &to_strings("acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab"),
+            &to_strings("cdfeb fcadb cdfeb cdbaf"),
+        )
+        .unwrap();
+
+        assert!(diagnostics.is_consistent());
+        assert_eq!(diagnostics.wirings.len(), 1);
+        assert_eq!(diagnostics.get_ambiguous_outputs(), Vec::<usize>::new());
+        assert_eq!(
+            diagnostics.describe(&display),
+            "1 wiring(s) deafgbc, output 5 3 5 3"
+        );
+    }
+
+    #[test]
+    fn incomplete_entry_test() {
+        let display = DisplayDefinition::seven_segment_digits();
+        // Only 1 is known, which can't tell apart digits with the same number of segments
+        // that both use exactly one of its segments.
+        let diagnostics =
+            diagnose_entry(&display, &to_strings("ab"), &to_strings("cdfbe cagedb ba")).unwrap();
+
+        assert!(diagnostics.is_consistent());
+        assert_eq!(diagnostics.wirings.len(), 18);
+        assert!(!diagnostics.wirings_truncated);
+        assert_eq!(diagnostics.get_ambiguous_outputs(), vec![0, 1]);
+        assert_eq!(
+            diagnostics.output_candidates,
+            vec![vec![2, 5], vec![0, 9], vec![1]]
+        );
+        assert!(diagnostics
+            .describe(&display)
+            .ends_with("output [2|5] [0|9] 1"));
+
+        let hex = DisplayDefinition::seven_segment_hex();
+        let diagnostics =
+            diagnose_entry(&hex, &to_strings("cf acf bcdf"), &to_strings("abcdfg")).unwrap();
+        assert_eq!(diagnostics.output_candidates, vec![vec![9, 10]]);
+    }
+
+    #[test]
+    fn contradicting_entry_test() {
+        let display = DisplayDefinition::seven_segment_digits();
+        // eafg would be 4, but it only shares one wire with 1 (ab) and with 7 (dab) while
+        // 4 has two segments of each. Dropping the patterns in order leaves 7 and 4.
+        let diagnostics = diagnose_entry(
+            &display,
+            &to_strings("ab acedgfb dab eafg"),
+            &to_strings("cdfbe"),
+        )
+        .unwrap();
+
+        assert!(!diagnostics.is_consistent());
+        assert_eq!(diagnostics.conflicting_inputs, vec![2, 3]);
+        assert_eq!(diagnostics.conflicting_outputs, Vec::<usize>::new());
+        assert_eq!(diagnostics.output_candidates, vec![Vec::<usize>::new()]);
+
+        let diagnostics =
+            diagnose_entry(&display, &to_strings("ab cdfbe"), &to_strings("ac")).unwrap();
+        assert_eq!(diagnostics.conflicting_inputs, vec![0]);
+        assert_eq!(diagnostics.conflicting_outputs, vec![0]);
+        assert_eq!(
+            diagnostics.describe(&display),
+            "inconsistent, inputs [0] and outputs [0] contradict each other"
+        );
+    }
+}
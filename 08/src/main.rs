@@ -1,24 +1,45 @@
 #![feature(test)]
 
-use crate::parse_input::{parse_main, ParseOutput, Segment};
-use std::collections::HashMap;
+use crate::decoder::{decode_entry, DisplayDefinition};
+use crate::diagnostics::diagnose_entry;
+use crate::parse_input::{parse, parse_main, ParseOutput};
 
+pub mod decoder;
+pub mod diagnostics;
 pub mod parse_input;
 
 type Solution = u32;
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("diagnose") {
+        let parse_output = parse(args.get(2).map_or("src/08.txt", String::as_str));
+        print_diagnostics(&parse_output);
+        return;
+    }
+
     let parse_output = parse_main();
     println!("Solution to part 1 is {}", part_1(&parse_output));
     println!("Solution to part 2 is {}", part_2(&parse_output));
 }
 
+/// One line per entry with the wirings that fit it and what its output could read.
+fn print_diagnostics(parse_output: &ParseOutput) {
+    let display = DisplayDefinition::seven_segment_digits();
+    for (i, (seg_in, seg_out)) in parse_output.iter().enumerate() {
+        match diagnose_entry(&display, seg_in, seg_out) {
+            Ok(diagnostics) => println!("{}: {}", i + 1, diagnostics.describe(&display)),
+            Err(err) => println!("{}: {}", i + 1, err),
+        }
+    }
+}
+
 fn part_1(parse_output: &ParseOutput) -> Solution {
     let mut count_numbers = 0;
     let segment_counts = [2, 4, 3, 7];
     for (_, seg_out) in parse_output {
         for segment in seg_out {
-            if segment_counts.contains(&segment.count) {
+            if segment_counts.contains(&segment.len()) {
                 count_numbers += 1;
             }
         }
@@ -26,113 +47,22 @@ fn part_1(parse_output: &ParseOutput) -> Solution {
     count_numbers
 }
 
+/// Entries that can't be read, because they contradict themselves or don't tell enough
+/// to pin down their output, are left out. `cargo run -- diagnose` shows which those are.
 fn part_2(parse_output: &ParseOutput) -> Solution {
+    let display = DisplayDefinition::seven_segment_digits();
     let mut count_numbers: u32 = 0;
     for (seg_in, seg_out) in parse_output {
-        let map = deduce_map(seg_in);
-
-        for (digit, segment) in seg_out.iter().enumerate() {
-            count_numbers += map[&segment.map] as u32 * 10_u32.pow(3_u32 - digit as u32);
+        if let Ok(digits) = decode_entry(&display, seg_in, seg_out) {
+            count_numbers += digits
+                .into_iter()
+                .fold(0, |number, digit| number * 10 + digit as u32);
         }
     }
 
     count_numbers
 }
 
-fn deduce_map(seg_in: &Vec<Segment>) -> HashMap<u8, u8> {
-    let mut segment_maps = [0u8; 10];
-
-    for seg in seg_in {
-        match seg.count {
-            2 => segment_maps[1] = seg.map,
-            4 => segment_maps[4] = seg.map,
-            3 => segment_maps[7] = seg.map,
-            7 => segment_maps[8] = seg.map,
-            _ => (),
-        }
-    }
-
-    for seg in seg_in {
-        match seg.count {
-            6 => {
-                // if 6 segments are set and only one of the segments of 1 is set, then this is the number 6
-                if (seg.map & segment_maps[1]).count_ones() == 1 {
-                    segment_maps[6] = seg.map;
-                }
-                // if 6 segments are set and all the segments of 4 are set, this is 9
-                if (seg.map & segment_maps[4]).count_ones() == 4 {
-                    segment_maps[9] = seg.map;
-                }
-            }
-            _ => (),
-        }
-    }
-
-    for seg in seg_in {
-        match seg.count {
-            6 => {
-                // if 6 segments are set and its neither 6 nor 9 this must be 0
-                if seg.map != segment_maps[6] && seg.map != segment_maps[9] {
-                    segment_maps[0] = seg.map;
-                }
-            }
-            5 => {
-                // if 5 segments are set and all 3 segments of 7 are set this is 3
-                if (seg.map & segment_maps[7]).count_ones() == 3 {
-                    segment_maps[3] = seg.map;
-                }
-                // if 5 segments are set and there is exactly 1 segment difference to 6 then this is 5
-                if (seg.map ^ segment_maps[6]).count_ones() == 1 {
-                    segment_maps[5] = seg.map;
-                }
-            }
-            _ => (),
-        }
-    }
-
-    for seg in seg_in {
-        match seg.count {
-            5 => {
-                // Two is the only one left
-                if seg.map != segment_maps[3] && seg.map != segment_maps[5] {
-                    segment_maps[2] = seg.map;
-                }
-            }
-            _ => (),
-        }
-    }
-
-    segment_maps
-        .into_iter()
-        .enumerate()
-        .map(|(i, segment_map)| (segment_map, i as u8))
-        .collect()
-
-    /*
-        abcdef <- 0,9  acdfg <- 2,5
-
-        0 = abcdef (6 bits are set and its not 6 or 9)
-        1 = bd (length is 2)
-        2 = acdfg (length is 5 and it differs from 5 in exactly 2 bits)
-        4 = bdeg (lengths is 4)
-        3 = agcbd (length is 5 and it has both bits of 1 set)
-        5 = abceg (length is 5 and only one bit of 6 is missing)
-        6 = abcefg (6 bits are set and only one the bits of 1 is set)
-        7 = dbc (length is 3)
-        8 = dfebcag (lengths is 7)
-        9 = abcdeg (6 bits are set and all the bits of 4 are set)
-
-       0 has (2 of 1) & (3 of 4) & (3 of 7) & (6 of 8)
-       1 = be
-       4 = bedf
-       7 = abe
-       8 = abcdefg
-
-       0 -> abef
-
-    */
-}
-
 #[cfg(test)]
 mod tests {
     extern crate test;
@@ -152,6 +82,17 @@ mod tests {
         assert_eq!(part_2(&parse_output), 61229);
     }
 
+    #[test]
+    pub fn test_part_2_skips_unreadable_entries() {
+        let to_strings = |patterns: &str| patterns.split_whitespace().map(String::from).collect();
+        let mut parse_output = parse_test();
+        // Only 1 is known, so the output could be several numbers.
+        parse_output.push((to_strings("ab"), to_strings("cdfbe cagedb")));
+        // eafg can't be 4, it only shares one wire with 1 and with 7.
+        parse_output.push((to_strings("ab acedgfb dab eafg"), to_strings("cdfbe")));
+        assert_eq!(part_2(&parse_output), 61229);
+    }
+
     #[bench]
     fn bench_parse(b: &mut Bencher) {
         b.iter(|| {
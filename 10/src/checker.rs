@@ -0,0 +1,326 @@
+/// A pair of delimiters and what it scores when its closer is the first illegal character
+/// of a line or when it is part of the completion of a line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delimiter {
+    pub open: char,
+    pub close: char,
+    pub corruption_score: u64,
+    pub completion_score: u64,
+}
+
+impl Delimiter {
+    pub fn new(open: char, close: char, corruption_score: u64, completion_score: u64) -> Self {
+        Delimiter {
+            open,
+            close,
+            corruption_score,
+            completion_score,
+        }
+    }
+}
+
+/// The delimiters a language is made of. A completion is scored by multiplying the score
+/// so far with `completion_multiplier` before adding the score of each closer.
+#[derive(Clone, Debug)]
+pub struct DelimiterTable {
+    delimiters: Vec<Delimiter>,
+    completion_multiplier: u64,
+}
+
+impl DelimiterTable {
+    pub fn new(delimiters: Vec<Delimiter>, completion_multiplier: u64) -> Self {
+        DelimiterTable {
+            delimiters,
+            completion_multiplier,
+        }
+    }
+
+    /// The chunks of the navigation subsystem and their scores from the puzzle.
+    pub fn navigation_subsystem() -> Self {
+        DelimiterTable::new(
+            vec![
+                Delimiter::new('(', ')', 3, 1),
+                Delimiter::new('[', ']', 57, 2),
+                Delimiter::new('{', '}', 1197, 3),
+                Delimiter::new('<', '>', 25137, 4),
+            ],
+            5,
+        )
+    }
+
+    pub fn get_delimiters(&self) -> &Vec<Delimiter> {
+        &self.delimiters
+    }
+
+    pub fn find_opening(&self, c: char) -> Option<&Delimiter> {
+        self.delimiters.iter().find(|delimiter| delimiter.open == c)
+    }
+
+    pub fn find_closing(&self, c: char) -> Option<&Delimiter> {
+        self.delimiters
+            .iter()
+            .find(|delimiter| delimiter.close == c)
+    }
+}
+
+/// Positions are counted in characters from the start of the line.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineStatus {
+    Valid,
+    /// A closer that doesn't match the last open chunk.
+    Corrupted {
+        position: usize,
+        expected: char,
+        found: char,
+    },
+    /// Chunks are left open at the end, `completion` closes them.
+    Incomplete {
+        completion: String,
+    },
+    /// A closer with no chunk open.
+    OverClosed {
+        position: usize,
+        found: char,
+    },
+    /// A character that is no delimiter of the table.
+    UnknownCharacter {
+        position: usize,
+        found: char,
+    },
+}
+
+pub struct Checker {
+    table: DelimiterTable,
+}
+
+/// Where reading a line stopped, along with the delimiters still open at that point.
+pub struct Scan<'a> {
+    pub status: LineStatus,
+    pub open_chunks: Vec<(usize, &'a Delimiter)>,
+}
+
+impl Checker {
+    pub fn new(table: DelimiterTable) -> Self {
+        Checker { table }
+    }
+
+    pub fn get_table(&self) -> &DelimiterTable {
+        &self.table
+    }
+
+    /// Reads the line up to the first problem. The open chunks are kept on a stack with
+    /// the position of their opener. A character that closes the last open chunk closes
+    /// it even if it could open a chunk as well, like `|` when it both opens and closes.
+    pub fn scan(&self, line: &str) -> Scan<'_> {
+        let mut open_chunks: Vec<(usize, &Delimiter)> = Vec::new();
+        for (position, c) in line.chars().enumerate() {
+            if open_chunks.last().is_some_and(|(_, open)| open.close == c) {
+                open_chunks.pop();
+                continue;
+            }
+            if let Some(delimiter) = self.table.find_opening(c) {
+                open_chunks.push((position, delimiter));
+                continue;
+            }
+            let status = match (self.table.find_closing(c), open_chunks.last()) {
+                (Some(_), Some((_, open))) => LineStatus::Corrupted {
+                    position,
+                    expected: open.close,
+                    found: c,
+                },
+                (Some(_), None) => LineStatus::OverClosed { position, found: c },
+                (None, _) => LineStatus::UnknownCharacter { position, found: c },
+            };
+            return Scan {
+                status,
+                open_chunks,
+            };
+        }
+
+        let status = match open_chunks.len() {
+            0 => LineStatus::Valid,
+            _ => LineStatus::Incomplete {
+                completion: open_chunks.iter().rev().map(|(_, d)| d.close).collect(),
+            },
+        };
+        Scan {
+            status,
+            open_chunks,
+        }
+    }
+
+    pub fn check_line(&self, line: &str) -> LineStatus {
+        self.scan(line).status
+    }
+
+    /// Score of the first illegal closer of a corrupted line.
+    pub fn get_corruption_score(&self, status: &LineStatus) -> Option<u64> {
+        match status {
+            LineStatus::Corrupted { found, .. } => {
+                self.table.find_closing(*found).map(|d| d.corruption_score)
+            }
+            _ => None,
+        }
+    }
+
+    /// Score of the completion of an incomplete line. `None` as well if the score doesn't
+    /// fit into a `u64`, which with the puzzle's table can happen from 28 closers on.
+    pub fn get_completion_score(&self, status: &LineStatus) -> Option<u64> {
+        match status {
+            LineStatus::Incomplete { completion } => {
+                completion.chars().try_fold(0u64, |score, c| {
+                    score
+                        .checked_mul(self.table.completion_multiplier)?
+                        .checked_add(self.table.find_closing(c)?.completion_score)
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_classification() {
+        let checker = Checker::new(DelimiterTable::navigation_subsystem());
+        assert_eq!(checker.check_line("([]){<>}"), LineStatus::Valid);
+        assert_eq!(checker.check_line(""), LineStatus::Valid);
+        assert_eq!(
+            checker.check_line("{([(<{}[<>[]}>{[]{[(<()>"),
+            LineStatus::Corrupted {
+                position: 12,
+                expected: ']',
+                found: '}'
+            }
+        );
+        assert_eq!(
+            checker.check_line("[({(<(())[]>[[{[]{<()<>>"),
+            LineStatus::Incomplete {
+                completion: "}}]])})]".to_string()
+            }
+        );
+        assert_eq!(
+            checker.check_line("()])"),
+            LineStatus::OverClosed {
+                position: 2,
+                found: ']'
+            }
+        );
+        assert_eq!(
+            checker.check_line("(a)"),
+            LineStatus::UnknownCharacter {
+                position: 1,
+                found: 'a'
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_scores() {
+        let checker = Checker::new(DelimiterTable::navigation_subsystem());
+        let corrupted = checker.check_line("[[<[([]))<([[{}[[()]]]");
+        assert_eq!(checker.get_corruption_score(&corrupted), Some(3));
+        assert_eq!(checker.get_completion_score(&corrupted), None);
+
+        let incomplete = checker.check_line("<{([{{}}[<[[[<>{}]]]>[]]");
+        assert_eq!(checker.get_completion_score(&incomplete), Some(294));
+        assert_eq!(checker.get_corruption_score(&incomplete), None);
+    }
+
+    #[test]
+    pub fn test_completion_score_overflow() {
+        let checker = Checker::new(DelimiterTable::navigation_subsystem());
+        let status = checker.check_line(&"<".repeat(27));
+        assert_eq!(
+            checker.get_completion_score(&status),
+            Some(5u64.pow(27) - 1)
+        );
+        let status = checker.check_line(&"<".repeat(28));
+        assert_eq!(checker.get_completion_score(&status), None);
+    }
+
+    #[test]
+    pub fn test_same_opener_and_closer() {
+        // Absolute values inside parentheses, `|` closes the chunk it opened.
+        let checker = Checker::new(DelimiterTable::new(
+            vec![
+                Delimiter::new('(', ')', 3, 1),
+                Delimiter::new('|', '|', 7, 2),
+            ],
+            5,
+        ));
+        assert_eq!(checker.check_line("|(||)|"), LineStatus::Valid);
+        assert_eq!(checker.check_line("(|()|)"), LineStatus::Valid);
+        assert_eq!(
+            checker.check_line("(|"),
+            LineStatus::Incomplete {
+                completion: "|)".to_string()
+            }
+        );
+        let status = checker.check_line("|(|");
+        assert_eq!(
+            status,
+            LineStatus::Incomplete {
+                completion: "|)|".to_string()
+            }
+        );
+        assert_eq!(checker.get_completion_score(&status), Some(2 * 25 + 5 + 2));
+        assert_eq!(
+            checker.check_line("(|)"),
+            LineStatus::Corrupted {
+                position: 2,
+                expected: '|',
+                found: ')'
+            }
+        );
+        assert_eq!(
+            checker.check_line("|)"),
+            LineStatus::Corrupted {
+                position: 1,
+                expected: '|',
+                found: ')'
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_custom_table() {
+        // Quotes that open and close with different characters, scored in base 10.
+        let checker = Checker::new(DelimiterTable::new(
+            vec![
+                Delimiter::new('«', '»', 100, 1),
+                Delimiter::new('(', ')', 10, 2),
+            ],
+            10,
+        ));
+        let status = checker.check_line("«(«");
+        assert_eq!(
+            status,
+            LineStatus::Incomplete {
+                completion: "»)»".to_string()
+            }
+        );
+        assert_eq!(checker.get_completion_score(&status), Some(121));
+
+        let status = checker.check_line("«(»");
+        assert_eq!(
+            status,
+            LineStatus::Corrupted {
+                position: 2,
+                expected: ')',
+                found: '»'
+            }
+        );
+        assert_eq!(checker.get_corruption_score(&status), Some(100));
+        assert_eq!(
+            checker.check_line("<>"),
+            LineStatus::UnknownCharacter {
+                position: 0,
+                found: '<'
+            }
+        );
+    }
+}
@@ -1,8 +1,10 @@
 #![feature(test)]
 
+use crate::checker::{Checker, DelimiterTable, LineStatus};
 use crate::parse_input::{parse_main, ParseOutput};
-use std::collections::VecDeque;
+pub mod checker;
 pub mod parse_input;
+pub mod repair;
 
 type Solution = u64;
 
@@ -13,71 +15,27 @@ fn main() {
 }
 
 fn part_1(parse_output: &ParseOutput) -> Solution {
-    let mut solution = 0;
-    for line in parse_output {
-        let mut stack: VecDeque<char> = VecDeque::new();
-        for char in line.chars() {
-            match char {
-                '(' | '[' | '{' | '<' => stack.push_back(char),
-                ')' | ']' | '}' | '>' => {
-                    let last_open_parenthesis = stack.pop_back().unwrap();
-                    if !do_parenthesis_match(last_open_parenthesis, char) {
-                        solution += get_solution_part_1_rating(char);
-                    }
-                }
-                e => unreachable!("Tried to match {}", e),
-            }
-        }
-    }
-
-    solution
-}
-
-fn do_parenthesis_match(opening: char, closing: char) -> bool {
-    match opening {
-        '(' => closing == ')',
-        '[' => closing == ']',
-        '{' => closing == '}',
-        '<' => closing == '>',
-        e => unreachable!("Tried to match {}", e),
-    }
-}
-
-fn get_solution_part_1_rating(closing_parenthesis: char) -> u64 {
-    match closing_parenthesis {
-        ')' => 3,
-        ']' => 57,
-        '}' => 1197,
-        '>' => 25137,
-        e => unreachable!("Tried to match {}", e),
-    }
+    let checker = Checker::new(DelimiterTable::navigation_subsystem());
+    parse_output
+        .iter()
+        .filter_map(|line| checker.get_corruption_score(&checker.check_line(line)))
+        .sum()
 }
 
+/// Panics if the completion score of an incomplete line overflows, rather than leaving
+/// the line out and shifting the middle score.
 fn part_2(parse_output: &ParseOutput) -> Solution {
-    let mut stack_solutions = Vec::new();
-    'line_loop: for line in parse_output {
-        let mut stack: VecDeque<char> = VecDeque::new();
-        for char in line.chars() {
-            match char {
-                '(' | '[' | '{' | '<' => stack.push_back(char),
-                ')' | ']' | '}' | '>' => {
-                    let last_open_parenthesis = stack.pop_back().unwrap();
-                    if !do_parenthesis_match(last_open_parenthesis, char) {
-                        // Discard line
-                        continue 'line_loop;
-                    }
-                }
-                e => unreachable!("Tried to match {}", e),
-            }
-        }
-
-        let mut stack_solution = 0;
-        for char in stack.iter().rev() {
-            stack_solution *= 5;
-            stack_solution += get_solution_part_2_rating(*char);
-        }
-        stack_solutions.push(stack_solution)
-    }
+    let checker = Checker::new(DelimiterTable::navigation_subsystem());
+    let mut stack_solutions: Vec<u64> = parse_output
+        .iter()
+        .map(|line| checker.check_line(line))
+        .filter(|status| matches!(status, LineStatus::Incomplete { .. }))
+        .map(|status| {
+            checker
+                .get_completion_score(&status)
+                .expect("completion score overflowed u64")
+        })
+        .collect();
     stack_solutions.sort();
 
     let middle = stack_solutions.len() / 2;
@@ -85,16 +43,6 @@ fn part_2(parse_output: &ParseOutput) -> Solution {
     stack_solutions[middle]
 }
 
-fn get_solution_part_2_rating(closing_parenthesis: char) -> u64 {
-    match closing_parenthesis {
-        '(' => 1,
-        '[' => 2,
-        '{' => 3,
-        '<' => 4,
-        e => unreachable!("Tried to match {}", e),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     extern crate test;
@@ -114,6 +62,14 @@ mod tests {
         assert_eq!(part_2(&parse_output), 288957);
     }
 
+    #[test]
+    #[should_panic(expected = "completion score overflowed u64")]
+    pub fn test_part_2_overflowing_line() {
+        let mut parse_output = parse_test();
+        parse_output.push("(".repeat(30));
+        part_2(&parse_output);
+    }
+
     #[bench]
     fn bench_parse(b: &mut Bencher) {
         b.iter(|| {
@@ -0,0 +1,336 @@
+use crate::checker::{Checker, LineStatus};
+
+/// A change to a line, positions are counted in characters of the original line. Inserted
+/// characters go in front of the character at `position`, or at the end of the line.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Edit {
+    Insert {
+        position: usize,
+        c: char,
+    },
+    Delete {
+        position: usize,
+        found: char,
+    },
+    Replace {
+        position: usize,
+        found: char,
+        c: char,
+    },
+}
+
+impl Edit {
+    pub fn get_position(&self) -> usize {
+        match self {
+            Edit::Insert { position, .. } => *position,
+            Edit::Delete { position, .. } => *position,
+            Edit::Replace { position, .. } => *position,
+        }
+    }
+}
+
+/// The line with the closers it is missing added, if it is incomplete.
+pub fn complete_line(checker: &Checker, line: &str) -> Option<String> {
+    match checker.check_line(line) {
+        LineStatus::Incomplete { completion } => Some(format!("{}{}", line, completion)),
+        _ => None,
+    }
+}
+
+/// Marks the character the line goes wrong at and tells what is wrong with it. Valid
+/// lines are returned as they are.
+pub fn annotate_line(checker: &Checker, line: &str) -> String {
+    let (position, message) = match checker.check_line(line) {
+        LineStatus::Valid => return line.to_string(),
+        LineStatus::Corrupted {
+            position,
+            expected,
+            found,
+        } => (
+            position,
+            format!("expected `{}`, found `{}`", expected, found),
+        ),
+        LineStatus::Incomplete { completion } => (
+            line.chars().count(),
+            format!("incomplete, `{}` is missing", completion),
+        ),
+        LineStatus::OverClosed { position, found } => {
+            (position, format!("`{}` closes nothing", found))
+        }
+        LineStatus::UnknownCharacter { position, found } => {
+            (position, format!("`{}` is no delimiter", found))
+        }
+    };
+    format!("{}\n{}^ {}", line, " ".repeat(position), message)
+}
+
+/// Edits that make the line valid, `None` if it has characters that aren't delimiters.
+/// Incomplete lines get their completion inserted at the end. Corrupted and over-closed
+/// lines get the fewest inserts, deletes and replacements that make them valid.
+///
+/// `cost[i][j]` is the fewest edits for the characters `i..j`: either the first one
+/// is fixed on its own, an opener gets its closer inserted at `j` and a stray closer is
+/// deleted, or it is paired with some later character `k` and both halves are fixed on
+/// their own.
+pub fn find_repair(checker: &Checker, line: &str) -> Option<Vec<Edit>> {
+    let table = checker.get_table();
+    let chars: Vec<char> = line.chars().collect();
+    match checker.check_line(line) {
+        LineStatus::Valid => return Some(Vec::new()),
+        LineStatus::UnknownCharacter { .. } => return None,
+        LineStatus::Incomplete { completion } => {
+            return Some(
+                completion
+                    .chars()
+                    .map(|c| Edit::Insert {
+                        position: chars.len(),
+                        c,
+                    })
+                    .collect(),
+            )
+        }
+        LineStatus::Corrupted { .. } | LineStatus::OverClosed { .. } => {}
+    }
+    if chars
+        .iter()
+        .any(|c| table.find_opening(*c).is_none() && table.find_closing(*c).is_none())
+    {
+        return None;
+    }
+
+    let n = chars.len();
+    // One replacement does if `a` can open or `b` can close, some characters can do both.
+    let pair_cost = |a: char, b: char| -> usize {
+        match table.find_opening(a) {
+            Some(open) if open.close == b => 0,
+            Some(_) => 1,
+            None if table.find_closing(b).is_some() => 1,
+            None => 2,
+        }
+    };
+
+    let mut cost = vec![vec![0usize; n + 1]; n + 1];
+    let mut pairing = vec![vec![None; n + 1]; n + 1];
+    for length in 1..=n {
+        for i in 0..=n - length {
+            let j = i + length;
+            cost[i][j] = cost[i + 1][j] + 1;
+            for k in i + 1..j {
+                let paired = pair_cost(chars[i], chars[k]) + cost[i + 1][k] + cost[k + 1][j];
+                if paired < cost[i][j] {
+                    cost[i][j] = paired;
+                    pairing[i][j] = Some(k);
+                }
+            }
+        }
+    }
+
+    let mut edits = Vec::new();
+    collect_edits(checker, &chars, &pairing, 0, n, &mut edits);
+    edits.sort_by_key(|edit| edit.get_position());
+    Some(edits)
+}
+
+fn collect_edits(
+    checker: &Checker,
+    chars: &[char],
+    pairing: &[Vec<Option<usize>>],
+    i: usize,
+    j: usize,
+    edits: &mut Vec<Edit>,
+) {
+    if i >= j {
+        return;
+    }
+    let table = checker.get_table();
+    match pairing[i][j] {
+        None => {
+            collect_edits(checker, chars, pairing, i + 1, j, edits);
+            match table.find_opening(chars[i]) {
+                Some(open) => edits.push(Edit::Insert {
+                    position: j,
+                    c: open.close,
+                }),
+                None => edits.push(Edit::Delete {
+                    position: i,
+                    found: chars[i],
+                }),
+            }
+        }
+        Some(k) => {
+            collect_edits(checker, chars, pairing, i + 1, k, edits);
+            let (a, b) = (chars[i], chars[k]);
+            match (table.find_opening(a), table.find_closing(b)) {
+                (Some(open), Some(_)) if open.close == b => {}
+                (Some(open), _) => edits.push(Edit::Replace {
+                    position: k,
+                    found: b,
+                    c: open.close,
+                }),
+                (None, Some(close)) => edits.push(Edit::Replace {
+                    position: i,
+                    found: a,
+                    c: close.open,
+                }),
+                (None, None) => {
+                    let open = table.find_opening(b).unwrap();
+                    edits.push(Edit::Replace {
+                        position: i,
+                        found: a,
+                        c: open.open,
+                    });
+                    edits.push(Edit::Replace {
+                        position: k,
+                        found: b,
+                        c: open.close,
+                    });
+                }
+            }
+            collect_edits(checker, chars, pairing, k + 1, j, edits);
+        }
+    }
+}
+
+/// Applies edits as returned by `find_repair`, sorted by position.
+pub fn apply_edits(line: &str, edits: &[Edit]) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut repaired = String::new();
+    let mut edits = edits.iter().peekable();
+    for position in 0..=chars.len() {
+        let mut kept = chars.get(position).cloned();
+        while let Some(edit) = edits.next_if(|edit| edit.get_position() == position) {
+            match edit {
+                Edit::Insert { c, .. } => repaired.push(*c),
+                Edit::Delete { .. } => kept = None,
+                Edit::Replace { c, .. } => kept = Some(*c),
+            }
+        }
+        if let Some(c) = kept {
+            repaired.push(c);
+        }
+    }
+    repaired
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::{Delimiter, DelimiterTable};
+    use crate::parse_input::parse_test;
+
+    fn checker() -> Checker {
+        Checker::new(DelimiterTable::navigation_subsystem())
+    }
+
+    #[test]
+    pub fn test_complete_line() {
+        assert_eq!(
+            complete_line(&checker(), "<{([{{}}[<[[[<>{}]]]>[]]"),
+            Some("<{([{{}}[<[[[<>{}]]]>[]]])}>".to_string())
+        );
+        assert_eq!(complete_line(&checker(), "(]"), None);
+    }
+
+    #[test]
+    pub fn test_annotate_line() {
+        assert_eq!(
+            annotate_line(&checker(), "[<>({}){}[([])<>]]"),
+            "[<>({}){}[([])<>]]"
+        );
+        assert_eq!(
+            annotate_line(&checker(), "[{[{({}]{}}([{[{{{}}([]"),
+            "[{[{({}]{}}([{[{{{}}([]\n       ^ expected `)`, found `]`"
+        );
+        assert_eq!(
+            annotate_line(&checker(), "(()"),
+            "(()\n   ^ incomplete, `)` is missing"
+        );
+        assert_eq!(
+            annotate_line(&checker(), "())"),
+            "())\n  ^ `)` closes nothing"
+        );
+    }
+
+    #[test]
+    pub fn test_small_repairs() {
+        let checker = checker();
+        assert_eq!(find_repair(&checker, "([])"), Some(vec![]));
+        assert_eq!(
+            find_repair(&checker, "(]"),
+            Some(vec![Edit::Replace {
+                position: 1,
+                found: ']',
+                c: ')'
+            }])
+        );
+        assert_eq!(
+            find_repair(&checker, "(()"),
+            Some(vec![Edit::Insert {
+                position: 3,
+                c: ')'
+            }])
+        );
+        assert_eq!(
+            find_repair(&checker, "())"),
+            Some(vec![Edit::Delete {
+                position: 2,
+                found: ')'
+            }])
+        );
+        let edits = find_repair(&checker, "([)]").unwrap();
+        assert_eq!(edits.len(), 2);
+        assert_eq!(
+            checker.check_line(&apply_edits("([)]", &edits)),
+            LineStatus::Valid
+        );
+
+        // Replacing one closer is cheaper than completing after the corruption.
+        assert_eq!(
+            find_repair(&checker, "{(]"),
+            Some(vec![
+                Edit::Replace {
+                    position: 2,
+                    found: ']',
+                    c: ')'
+                },
+                Edit::Insert {
+                    position: 3,
+                    c: '}'
+                }
+            ])
+        );
+        assert_eq!(find_repair(&checker, "(a)"), None);
+    }
+
+    #[test]
+    pub fn test_repairs_make_lines_valid() {
+        let checker = checker();
+        for line in parse_test() {
+            let edits = find_repair(&checker, &line).unwrap();
+            let repaired = apply_edits(&line, &edits);
+            assert_eq!(checker.check_line(&repaired), LineStatus::Valid, "{}", line);
+
+            // Lines that are only incomplete need nothing but their completion.
+            if let Some(completed) = complete_line(&checker, &line) {
+                assert_eq!(edits.len(), completed.len() - line.len());
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_repairs_with_same_opener_and_closer() {
+        let checker = Checker::new(DelimiterTable::new(
+            vec![
+                Delimiter::new('(', ')', 3, 1),
+                Delimiter::new('|', '|', 7, 2),
+            ],
+            5,
+        ));
+        for (line, edit_count) in [("(|)", 1), ("|)", 1), ("(|(|)", 1), (")|", 1)] {
+            let edits = find_repair(&checker, line).unwrap();
+            let repaired = apply_edits(line, &edits);
+            assert_eq!(checker.check_line(&repaired), LineStatus::Valid, "{}", line);
+            assert_eq!(edits.len(), edit_count, "{}", line);
+        }
+    }
+}
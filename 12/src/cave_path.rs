@@ -0,0 +1,100 @@
+use crate::cave_system::{CaveId, CaveSystem};
+use crate::visit_policy::VisitPolicy;
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct CavePath<'a> {
+    policy: &'a VisitPolicy,
+    limited_caves_visited: HashMap<CaveId, u8>,
+    path: Vec<CaveId>,
+    extra_visits_used: u8,
+}
+
+impl<'a> CavePath<'a> {
+    pub fn new(start_id: CaveId, policy: &'a VisitPolicy) -> CavePath<'a> {
+        CavePath {
+            policy,
+            limited_caves_visited: HashMap::new(),
+            path: vec![start_id],
+            extra_visits_used: 0,
+        }
+    }
+
+    pub fn visit_cave(&mut self, id: &CaveId) -> bool {
+        if self.policy.is_forbidden(id) {
+            return false;
+        }
+        if !self.policy.is_limited(id) {
+            return true;
+        }
+
+        let visited = self.limited_caves_visited.entry(id.clone()).or_insert(0);
+
+        if *visited < self.policy.get_visit_limit(id) {
+            *visited += 1;
+            return true;
+        }
+        if self.extra_visits_used < self.policy.extra_visits {
+            *visited += 1;
+            self.extra_visits_used += 1;
+            return true;
+        }
+
+        return false;
+    }
+
+    pub fn has_visited_required_caves(&self) -> bool {
+        self.policy
+            .required_caves
+            .iter()
+            .all(|id| self.path.contains(id))
+    }
+}
+
+/// Walks the cave system depth first and hands out one path at a time, so only the
+/// paths on the current branch are kept in memory.
+pub struct CavePathIter<'a> {
+    cave_system: &'a CaveSystem,
+    active_paths: Vec<CavePath<'a>>,
+}
+
+impl<'a> CavePathIter<'a> {
+    pub fn new(cave_system: &'a CaveSystem, start: CavePath<'a>) -> CavePathIter<'a> {
+        CavePathIter {
+            cave_system,
+            active_paths: vec![start],
+        }
+    }
+}
+
+impl<'a> Iterator for CavePathIter<'a> {
+    type Item = Vec<CaveId>;
+
+    fn next(&mut self) -> Option<Vec<CaveId>> {
+        while let Some(path) = self.active_paths.pop() {
+            let cave_id = path.path.last().unwrap();
+            if *cave_id == self.cave_system.get_end_id() {
+                if path.has_visited_required_caves() {
+                    return Some(path.path);
+                }
+                continue;
+            }
+
+            for cave_id in &self.cave_system.get_adjacent_cave_ids(cave_id) {
+                if *cave_id == self.cave_system.get_start_id() {
+                    continue;
+                }
+
+                let mut new_path = path.clone();
+                if !new_path.visit_cave(cave_id) {
+                    continue;
+                }
+
+                new_path.path.push(cave_id.clone());
+                self.active_paths.push(new_path);
+            }
+        }
+
+        None
+    }
+}
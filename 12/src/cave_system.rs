@@ -1,78 +1,121 @@
-use std::collections::hash_map::{Iter, Keys};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 pub type CaveId = String;
 
-pub struct CaveSystem {
-    start_id: CaveId,
-    cave_map: HashMap<CaveId, Cave>,
-}
+/// Position of a cave in the order it was first seen.
+pub type CaveIndex = usize;
 
-#[derive(Debug)]
-pub struct Cave {
-    id: CaveId,
-    cave_connections: HashSet<CaveId>,
-    is_small: bool,
-}
-
-impl Cave {
-    pub fn new(id: String, is_small: bool) -> Cave {
-        Cave {
-            id,
-            cave_connections: HashSet::new(),
-            is_small,
-        }
-    }
+/// Caves are interned when they are first connected, everything else refers to them by
+/// their index.
+pub struct CaveSystem {
+    cave_ids: Vec<CaveId>,
+    cave_indices: HashMap<CaveId, CaveIndex>,
+    adjacent_caves: Vec<Vec<CaveIndex>>,
+    start: CaveIndex,
+    end: CaveIndex,
 }
 
 impl CaveSystem {
     pub fn new() -> CaveSystem {
-        let start_id = "start".to_string();
-        let start = Cave::new(start_id.clone(), false);
-        let mut nodes = HashMap::new();
-        nodes.insert(start_id.clone(), start);
-
-        CaveSystem {
-            start_id,
-            cave_map: nodes,
-        }
+        CaveSystem::with_start_and_end(&"start".to_string(), &"end".to_string())
+    }
+
+    pub fn with_start_and_end(start_id: &CaveId, end_id: &CaveId) -> CaveSystem {
+        let mut cave_system = CaveSystem {
+            cave_ids: Vec::new(),
+            cave_indices: HashMap::new(),
+            adjacent_caves: Vec::new(),
+            start: 0,
+            end: 0,
+        };
+        cave_system.start = cave_system.add_cave(start_id);
+        cave_system.end = cave_system.add_cave(end_id);
+        cave_system
     }
 
     pub fn get_start_id(&self) -> CaveId {
-        self.start_id.clone()
+        self.cave_ids[self.start].clone()
+    }
+
+    pub fn get_end_id(&self) -> CaveId {
+        self.cave_ids[self.end].clone()
+    }
+
+    pub fn get_start(&self) -> CaveIndex {
+        self.start
+    }
+
+    pub fn get_end(&self) -> CaveIndex {
+        self.end
+    }
+
+    pub fn len(&self) -> usize {
+        self.cave_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cave_ids.is_empty()
+    }
+
+    pub fn get_cave_id(&self, index: CaveIndex) -> &CaveId {
+        &self.cave_ids[index]
+    }
+
+    pub fn get_cave_index(&self, id: &CaveId) -> Option<CaveIndex> {
+        self.cave_indices.get(id).copied()
+    }
+
+    pub fn get_cave_ids(&self) -> &Vec<CaveId> {
+        &self.cave_ids
+    }
+
+    pub fn get_adjacent_caves(&self, index: CaveIndex) -> &Vec<CaveIndex> {
+        &self.adjacent_caves[index]
     }
 
     pub fn get_adjacent_cave_ids(&self, id: &CaveId) -> Vec<CaveId> {
-        let mut adjacent_cave_ids = Vec::new();
+        match self.get_cave_index(id) {
+            Some(index) => self.adjacent_caves[index]
+                .iter()
+                .map(|a| self.cave_ids[*a].clone())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
 
-        if let Some(cave) = self.cave_map.get(id) {
-            for cave_id in cave.cave_connections.iter() {
-                adjacent_cave_ids.push(cave_id.clone());
+    /// Every connection once, in the order the caves were first seen.
+    pub fn get_connections(&self) -> Vec<(CaveIndex, CaveIndex)> {
+        let mut connections = Vec::new();
+        for (a, adjacent_caves) in self.adjacent_caves.iter().enumerate() {
+            for b in adjacent_caves {
+                if a < *b {
+                    connections.push((a, *b));
+                }
             }
         }
-        adjacent_cave_ids
+        connections
     }
 
-    pub fn connect_caves(&mut self, start_id: &String, end_id: &String) {
-        self.add_cave(start_id, is_small_cave(start_id));
-        self.add_cave(end_id, is_small_cave(end_id));
-
-        let start_cave = self.cave_map.get_mut(start_id).unwrap();
-        if let None = start_cave.cave_connections.get(end_id) {
-            start_cave.cave_connections.insert(end_id.clone());
-        }
+    pub fn connect_caves(&mut self, start_id: &CaveId, end_id: &CaveId) {
+        let start = self.add_cave(start_id);
+        let end = self.add_cave(end_id);
 
-        let end_cave = self.cave_map.get_mut(end_id).unwrap();
-        if let None = end_cave.cave_connections.get(start_id) {
-            end_cave.cave_connections.insert(start_id.clone());
+        if !self.adjacent_caves[start].contains(&end) {
+            self.adjacent_caves[start].push(end);
+            self.adjacent_caves[end].push(start);
         }
     }
 
-    pub fn add_cave(&mut self, id: &String, is_small: bool) {
-        if let None = self.cave_map.get(id) {
-            self.cave_map
-                .insert(id.clone(), Cave::new(id.clone(), is_small));
+    pub fn add_cave(&mut self, id: &CaveId) -> CaveIndex {
+        if let Some(index) = self.cave_indices.get(id) {
+            return *index;
         }
+
+        let index = self.cave_ids.len();
+        self.cave_ids.push(id.clone());
+        self.cave_indices.insert(id.clone(), index);
+        self.adjacent_caves.push(Vec::new());
+        index
     }
 }
 
@@ -0,0 +1,563 @@
+use crate::cave_system::{is_small_cave, CaveId, CaveSystem};
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+fn quote(id: &CaveId) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Unlike DOT strings, JSON strings can't hold control characters as they are.
+fn json_quote(id: &CaveId) -> String {
+    let mut quoted = String::from("\"");
+    for c in id.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Small caves are drawn as circles and big ones as boxes. Start and end are marked with
+/// `start=true` and `end=true`, which Graphviz ignores but `from_dot` reads back.
+pub fn to_dot(cave_system: &CaveSystem) -> String {
+    let mut dot = String::from("graph caves {\n");
+    for (index, id) in cave_system.get_cave_ids().iter().enumerate() {
+        let mut attributes = vec![if is_small_cave(id) {
+            "shape=circle"
+        } else {
+            "shape=box"
+        }];
+        if index == cave_system.get_start() {
+            attributes.push("start=true");
+        }
+        if index == cave_system.get_end() {
+            attributes.push("end=true");
+        }
+        dot.push_str(&format!("    {} [{}];\n", quote(id), attributes.join(", ")));
+    }
+    for (a, b) in cave_system.get_connections() {
+        dot.push_str(&format!(
+            "    {} -- {};\n",
+            quote(cave_system.get_cave_id(a)),
+            quote(cave_system.get_cave_id(b))
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Reads undirected graphs as written by `to_dot`: statements optionally separated by `;`,
+/// either a node with optional attributes or a chain of edges `a -- b -- c`. Ids can be
+/// quoted, in which case they may contain anything, separators included. Default
+/// attributes like `node [shape=box]` and graph attributes like `rankdir=LR` are skipped.
+pub fn from_dot(dot: &str) -> Result<CaveSystem, String> {
+    let mut tokens = tokenize_dot(dot)?.into_iter().peekable();
+    let header_error = "expected an undirected graph: graph name { ... }";
+
+    if tokens.peek() == Some(&DotToken::Keyword("strict".to_string())) {
+        tokens.next();
+    }
+    if tokens.next() != Some(DotToken::Keyword("graph".to_string())) {
+        return Err(header_error.to_string());
+    }
+    if let Some(DotToken::Id(_)) = tokens.peek() {
+        tokens.next();
+    }
+    if tokens.next() != Some(DotToken::Symbol('{')) {
+        return Err(header_error.to_string());
+    }
+
+    let mut start_id = "start".to_string();
+    let mut end_id = "end".to_string();
+    let mut caves: Vec<CaveId> = Vec::new();
+    let mut connections: Vec<(CaveId, CaveId)> = Vec::new();
+
+    loop {
+        let id = match tokens.next() {
+            Some(DotToken::Symbol('}')) => break,
+            Some(DotToken::Symbol(';')) => continue,
+            Some(DotToken::Keyword(keyword))
+                if matches!(keyword.as_str(), "node" | "edge" | "graph") =>
+            {
+                parse_dot_attributes(&mut tokens)?;
+                continue;
+            }
+            Some(DotToken::Id(id)) => id,
+            Some(token) => return Err(format!("unexpected {}", token)),
+            None => return Err("the graph body is not closed".to_string()),
+        };
+
+        match tokens.peek() {
+            Some(DotToken::Symbol('=')) => {
+                tokens.next();
+                if !matches!(tokens.next(), Some(DotToken::Id(_))) {
+                    return Err(format!("expected a value for graph attribute `{}`", id));
+                }
+            }
+            Some(DotToken::Edge) => {
+                let mut previous = id;
+                while tokens.next_if_eq(&DotToken::Edge).is_some() {
+                    match tokens.next() {
+                        Some(DotToken::Id(next)) => {
+                            connections.push((previous, next.clone()));
+                            previous = next;
+                        }
+                        _ => return Err(format!("expected a cave after `{} --`", previous)),
+                    }
+                }
+                parse_dot_attributes(&mut tokens)?;
+            }
+            Some(DotToken::DirectedEdge) => {
+                return Err(format!(
+                    "cave connections can't be directed: `{} -> ...`",
+                    id
+                ));
+            }
+            _ => {
+                for (key, value) in parse_dot_attributes(&mut tokens)? {
+                    match (key.as_str(), value.as_str()) {
+                        ("start", "true") => start_id = id.clone(),
+                        ("end", "true") => end_id = id.clone(),
+                        _ => {}
+                    }
+                }
+                caves.push(id);
+            }
+        }
+    }
+    if let Some(token) = tokens.next() {
+        return Err(format!("unexpected {} after the graph body", token));
+    }
+
+    let mut cave_system = CaveSystem::with_start_and_end(&start_id, &end_id);
+    for id in &caves {
+        cave_system.add_cave(id);
+    }
+    for (a, b) in &connections {
+        cave_system.connect_caves(a, b);
+    }
+    Ok(cave_system)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum DotToken {
+    Id(String),
+    /// `graph`, `node` and the like, which only count as keywords when they aren't quoted.
+    Keyword(String),
+    Symbol(char),
+    Edge,
+    DirectedEdge,
+}
+
+impl fmt::Display for DotToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DotToken::Id(id) => write!(f, "{}", quote(id)),
+            DotToken::Keyword(keyword) => write!(f, "`{}`", keyword),
+            DotToken::Symbol(c) => write!(f, "`{}`", c),
+            DotToken::Edge => write!(f, "`--`"),
+            DotToken::DirectedEdge => write!(f, "`->`"),
+        }
+    }
+}
+
+/// Splits DOT into tokens before anything else looks at it, so that separators inside
+/// quoted ids stay part of the id. `//` comments are skipped.
+fn tokenize_dot(dot: &str) -> Result<Vec<DotToken>, String> {
+    let mut chars = dot.chars().peekable();
+    let mut tokens = Vec::new();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '"' => tokens.push(DotToken::Id(parse_dot_string(&mut chars)?)),
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' => {
+                chars.next();
+                tokens.push(DotToken::Symbol(c));
+            }
+            '-' => {
+                chars.next();
+                match chars.next() {
+                    Some('-') => tokens.push(DotToken::Edge),
+                    Some('>') => tokens.push(DotToken::DirectedEdge),
+                    _ => return Err("expected `--` or `->` after `-`".to_string()),
+                }
+            }
+            '/' => {
+                chars.next();
+                if chars.next() != Some('/') {
+                    return Err("expected `//` to start a comment".to_string());
+                }
+                while chars.next_if(|c| *c != '\n').is_some() {}
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {
+                let mut id = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+                {
+                    id.push(c);
+                }
+                let keyword = id.to_lowercase();
+                match matches!(
+                    keyword.as_str(),
+                    "strict" | "graph" | "digraph" | "subgraph" | "node" | "edge"
+                ) {
+                    true => tokens.push(DotToken::Keyword(keyword)),
+                    false => tokens.push(DotToken::Id(id)),
+                }
+            }
+            c => return Err(format!("unexpected character `{}`", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// The reverse of `quote`.
+fn parse_dot_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    chars.next();
+    let mut string = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(string),
+            Some('\\') => match chars.next() {
+                Some(c @ ('"' | '\\')) => string.push(c),
+                Some(c) => {
+                    string.push('\\');
+                    string.push(c);
+                }
+                None => return Err("unterminated string".to_string()),
+            },
+            Some(c) => string.push(c),
+            None => return Err("unterminated string".to_string()),
+        }
+    }
+}
+
+/// An optional `[key=value, ...]` list.
+fn parse_dot_attributes(
+    tokens: &mut Peekable<impl Iterator<Item = DotToken>>,
+) -> Result<Vec<(String, String)>, String> {
+    let mut attributes = Vec::new();
+    if tokens.next_if_eq(&DotToken::Symbol('[')).is_none() {
+        return Ok(attributes);
+    }
+    loop {
+        match tokens.next() {
+            Some(DotToken::Symbol(']')) => return Ok(attributes),
+            Some(DotToken::Symbol(',' | ';')) => continue,
+            Some(DotToken::Id(key)) => {
+                if tokens.next() != Some(DotToken::Symbol('=')) {
+                    return Err(format!("expected `=` after attribute `{}`", key));
+                }
+                match tokens.next() {
+                    Some(DotToken::Id(value)) => attributes.push((key, value)),
+                    _ => return Err(format!("expected a value for attribute `{}`", key)),
+                }
+            }
+            Some(token) => return Err(format!("unexpected {} in attribute list", token)),
+            None => return Err("unclosed attribute list".to_string()),
+        }
+    }
+}
+
+pub fn to_json(cave_system: &CaveSystem) -> String {
+    let caves: Vec<String> = cave_system.get_cave_ids().iter().map(json_quote).collect();
+    let connections: Vec<String> = cave_system
+        .get_connections()
+        .iter()
+        .map(|(a, b)| {
+            format!(
+                "    [{}, {}]",
+                json_quote(cave_system.get_cave_id(*a)),
+                json_quote(cave_system.get_cave_id(*b))
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\n  \"start\": {},\n  \"end\": {},\n  \"caves\": [{}],\n  \"connections\": [\n{}\n  ]\n}}\n",
+        json_quote(&cave_system.get_start_id()),
+        json_quote(&cave_system.get_end_id()),
+        caves.join(", "),
+        connections.join(",\n")
+    )
+}
+
+/// Reads the format written by `to_json`. `start`, `end` and `caves` are optional.
+pub fn from_json(json: &str) -> Result<CaveSystem, String> {
+    let mut chars = json.chars().peekable();
+    let fields = match parse_json_value(&mut chars)? {
+        JsonValue::Object(fields) => fields,
+        _ => return Err("expected a JSON object".to_string()),
+    };
+    skip_whitespace(&mut chars);
+    if chars.peek().is_some() {
+        return Err("unexpected characters after the JSON object".to_string());
+    }
+
+    let get_string = |key: &str, default: &str| match fields.iter().find(|(k, _)| k == key) {
+        Some((_, JsonValue::String(s))) => Ok(s.clone()),
+        Some(_) => Err(format!("`{}` has to be a string", key)),
+        None => Ok(default.to_string()),
+    };
+    let get_array = |key: &str| match fields.iter().find(|(k, _)| k == key) {
+        Some((_, JsonValue::Array(a))) => Ok(a.clone()),
+        Some(_) => Err(format!("`{}` has to be an array", key)),
+        None => Ok(Vec::new()),
+    };
+
+    let mut cave_system =
+        CaveSystem::with_start_and_end(&get_string("start", "start")?, &get_string("end", "end")?);
+    for cave in get_array("caves")? {
+        match cave {
+            JsonValue::String(id) => cave_system.add_cave(&id),
+            _ => return Err("`caves` has to contain strings".to_string()),
+        };
+    }
+    for connection in get_array("connections")? {
+        match connection {
+            JsonValue::Array(ids) => match ids.as_slice() {
+                [JsonValue::String(a), JsonValue::String(b)] => cave_system.connect_caves(a, b),
+                _ => return Err("a connection has to be a pair of cave names".to_string()),
+            },
+            _ => return Err("`connections` has to contain arrays".to_string()),
+        }
+    }
+    Ok(cave_system)
+}
+
+/// Just the parts of JSON a cave system is made of.
+#[derive(Clone, Debug)]
+enum JsonValue {
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn expect_char(chars: &mut Peekable<Chars>, expected: char) -> Result<(), String> {
+    skip_whitespace(chars);
+    match chars.next() {
+        Some(c) if c == expected => Ok(()),
+        Some(c) => Err(format!("expected `{}` but found `{}`", expected, c)),
+        None => Err(format!("expected `{}` but the input ended", expected)),
+    }
+}
+
+fn parse_json_value(chars: &mut Peekable<Chars>) -> Result<JsonValue, String> {
+    skip_whitespace(chars);
+    match chars.peek() {
+        Some('"') => parse_json_string(chars).map(JsonValue::String),
+        Some('[') => {
+            chars.next();
+            let mut values = Vec::new();
+            skip_whitespace(chars);
+            if chars.peek() == Some(&']') {
+                chars.next();
+                return Ok(JsonValue::Array(values));
+            }
+            loop {
+                values.push(parse_json_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some(']') => return Ok(JsonValue::Array(values)),
+                    _ => return Err("expected `,` or `]` in array".to_string()),
+                }
+            }
+        }
+        Some('{') => {
+            chars.next();
+            let mut fields = Vec::new();
+            skip_whitespace(chars);
+            if chars.peek() == Some(&'}') {
+                chars.next();
+                return Ok(JsonValue::Object(fields));
+            }
+            loop {
+                skip_whitespace(chars);
+                let key = parse_json_string(chars)?;
+                expect_char(chars, ':')?;
+                fields.push((key, parse_json_value(chars)?));
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => continue,
+                    Some('}') => return Ok(JsonValue::Object(fields)),
+                    _ => return Err("expected `,` or `}` in object".to_string()),
+                }
+            }
+        }
+        Some(c) => Err(format!("unsupported JSON value starting with `{}`", c)),
+        None => Err("unexpected end of input".to_string()),
+    }
+}
+
+fn parse_json_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    expect_char(chars, '"')?;
+    let mut string = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(string),
+            Some('\\') => match chars.next() {
+                Some('n') => string.push('\n'),
+                Some('t') => string.push('\t'),
+                Some('r') => string.push('\r'),
+                Some(c @ ('"' | '\\' | '/')) => string.push(c),
+                Some('u') => {
+                    let hex: String = chars.take(4).collect();
+                    match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                        Some(c) if hex.len() == 4 => string.push(c),
+                        _ => return Err(format!("invalid escape sequence `\\u{}`", hex)),
+                    }
+                }
+                _ => return Err("unsupported escape sequence in string".to_string()),
+            },
+            Some(c) => string.push(c),
+            None => return Err("unterminated string".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use crate::cave_system::CaveSystem;
+    use crate::graph_format::{from_dot, from_json, to_dot, to_json};
+    use crate::parse_input::{parse, read_test};
+
+    fn assert_same_cave_system(a: &CaveSystem, b: &CaveSystem) {
+        assert_eq!(a.get_start_id(), b.get_start_id());
+        assert_eq!(a.get_end_id(), b.get_end_id());
+        assert_eq!(a.get_cave_ids(), b.get_cave_ids());
+        assert_eq!(a.get_connections(), b.get_connections());
+    }
+
+    #[test]
+    pub fn test_to_dot() {
+        let cave_system = parse(&"start-A\nA-b\nb-end".to_string());
+        assert_eq!(
+            to_dot(&cave_system),
+            "graph caves {
+    \"start\" [shape=circle, start=true];
+    \"end\" [shape=circle, end=true];
+    \"A\" [shape=box];
+    \"b\" [shape=circle];
+    \"start\" -- \"A\";
+    \"end\" -- \"b\";
+    \"A\" -- \"b\";
+}
+"
+        );
+    }
+
+    #[test]
+    pub fn test_dot_round_trip() {
+        let cave_system = parse(&read_test());
+        assert_same_cave_system(&cave_system, &from_dot(&to_dot(&cave_system)).unwrap());
+    }
+
+    #[test]
+    pub fn test_from_dot() {
+        let cave_system =
+            from_dot("graph { a [start=true]; z [end=true]; a -- B; B -- z }").unwrap();
+        assert_eq!(cave_system.get_start_id(), "a");
+        assert_eq!(cave_system.get_end_id(), "z");
+        assert_eq!(
+            cave_system.get_adjacent_cave_ids(&"B".to_string()),
+            vec!["a", "z"]
+        );
+
+        assert!(from_dot("digraph { a -> b }").is_err());
+        assert!(from_dot("graph { a -> b }").is_err());
+        assert!(from_dot("graph { a [start=true }").is_err());
+        assert!(from_dot("graph { \"a }").is_err());
+    }
+
+    #[test]
+    pub fn test_from_dot_quoted_ids() {
+        let cave_system = from_dot(
+            "graph {\n    // comment\n    \"in;\" [start=true]\n    \"in;\" -- \"x -- y\" -- \"{out}\\n\";\n}",
+        )
+        .unwrap();
+        assert_eq!(cave_system.get_start_id(), "in;");
+        assert_eq!(
+            cave_system.get_adjacent_cave_ids(&"x -- y".to_string()),
+            vec!["in;", "{out}\\n"]
+        );
+
+        let mut cave_system =
+            CaveSystem::with_start_and_end(&"a;b".to_string(), &"say \"hi\"".to_string());
+        cave_system.connect_caves(&"a;b".to_string(), &"C [x=1]".to_string());
+        cave_system.connect_caves(&"C [x=1]".to_string(), &"line\nbreak\\".to_string());
+        cave_system.connect_caves(&"line\nbreak\\".to_string(), &"say \"hi\"".to_string());
+        assert_same_cave_system(&cave_system, &from_dot(&to_dot(&cave_system)).unwrap());
+    }
+
+    #[test]
+    pub fn test_from_dot_defaults_and_graph_attributes() {
+        let cave_system = from_dot(
+            "strict graph G {\n    rankdir=LR;\n    node [shape=circle];\n    edge [color=red]\n    graph [label=\"caves\"]\n    start -- A -- end\n}",
+        )
+        .unwrap();
+        assert_eq!(*cave_system.get_cave_ids(), vec!["start", "end", "A"]);
+        assert_eq!(cave_system.get_connections(), vec![(0, 2), (1, 2)]);
+
+        // Quoted, they are just caves.
+        let cave_system = from_dot("graph { start -- \"node\" -- \"graph\" -- end }").unwrap();
+        assert_eq!(
+            *cave_system.get_cave_ids(),
+            vec!["start", "end", "node", "graph"]
+        );
+
+        assert!(from_dot("graph { rankdir= }").is_err());
+        assert!(from_dot("graph { subgraph { a } }").is_err());
+    }
+
+    #[test]
+    pub fn test_json_round_trip() {
+        let cave_system = parse(&read_test());
+        assert_same_cave_system(&cave_system, &from_json(&to_json(&cave_system)).unwrap());
+    }
+
+    #[test]
+    pub fn test_json_control_characters() {
+        let mut cave_system =
+            CaveSystem::with_start_and_end(&"line\nbreak".to_string(), &"say \"hi\"\\".to_string());
+        cave_system.connect_caves(&"line\nbreak".to_string(), &"tab\there".to_string());
+        cave_system.connect_caves(&"tab\there".to_string(), &"bell\u{7}\u{1f}".to_string());
+        cave_system.connect_caves(&"bell\u{7}\u{1f}".to_string(), &"say \"hi\"\\".to_string());
+
+        let json = to_json(&cave_system);
+        assert!(json.contains("\"bell\\u0007\\u001f\""));
+        assert!(!json.chars().any(|c| c < ' ' && c != '\n'));
+        assert_same_cave_system(&cave_system, &from_json(&json).unwrap());
+    }
+
+    #[test]
+    pub fn test_from_json() {
+        let cave_system = from_json(
+            r#"{"start": "in", "end": "out", "connections": [["in", "X"], ["X", "out"]]}"#,
+        )
+        .unwrap();
+        assert_eq!(cave_system.get_start_id(), "in");
+        assert_eq!(cave_system.get_end_id(), "out");
+        assert_eq!(cave_system.get_connections(), vec![(0, 2), (1, 2)]);
+
+        assert!(from_json(r#"{"connections": [["in"]]}"#).is_err());
+        assert!(from_json(r#"{"connections": [["in", "X"]]"#).is_err());
+        assert!(from_json(r#"{"start": 1}"#).is_err());
+    }
+}
@@ -1,153 +1,263 @@
 #![feature(test)]
 
-use crate::cave_system::{is_small_cave, CaveId};
-use crate::parse_input::{parse, read_main, ParseOutput};
-use std::cmp::max;
-use std::collections::{HashMap, VecDeque};
+use crate::cave_path::{CavePath, CavePathIter};
+use crate::graph_format::{to_dot, to_json};
+use crate::parse_input::{parse, parse_file, read_main, ParseOutput};
+use crate::path_count::PathCounter;
+use crate::visit_policy::VisitPolicy;
 
+pub mod cave_path;
 pub mod cave_system;
+pub mod graph_format;
 pub mod parse_input;
-type Solution = u32;
+pub mod path_count;
+pub mod visit_policy;
+type Solution = u64;
 
+/// `cargo run -- [paths|dot|json] [input]` additionally prints every path of part 2 or
+/// the cave system as DOT or JSON. The input can be a puzzle input or a DOT or JSON export.
 fn main() {
-    let parse_output = parse(&read_main());
+    let args: Vec<String> = std::env::args().collect();
+    let parse_output = match args.get(2) {
+        Some(file_name) => parse_file(file_name).unwrap(),
+        None => parse(&read_main()),
+    };
     println!("Solution to part 1 is {}", part_1(&parse_output));
     println!("Solution to part 2 is {}", part_2(&parse_output));
+
+    match args.get(1).map(|a| a.as_str()) {
+        Some("paths") => {
+            match enumerate_paths(&parse_output, &VisitPolicy::with_one_double_visit()) {
+                Ok(paths) => paths.for_each(|path| println!("{}", path.join(","))),
+                Err(error) => eprintln!("{}", error),
+            }
+        }
+        Some("dot") => print!("{}", to_dot(&parse_output)),
+        Some("json") => print!("{}", to_json(&parse_output)),
+        _ => {}
+    }
 }
 
-#[derive(Clone, Debug)]
-struct CavePath {
-    small_caves_visited: HashMap<CaveId, u8>,
-    path: Vec<CaveId>,
-    max_visit_count: u8,
-    allow_one_above_max: bool,
+fn part_1(parse_output: &ParseOutput) -> Solution {
+    PathCounter::new(parse_output, &VisitPolicy::new())
+        .unwrap()
+        .count()
 }
 
-impl CavePath {
-    pub fn visit_small_cave(&mut self, id: &CaveId) -> bool {
-        if let None = self.small_caves_visited.get(id) {
-            self.small_caves_visited.insert(id.clone(), 0);
-        }
-        let visited = self.small_caves_visited.get(id).unwrap();
+fn part_2(parse_output: &ParseOutput) -> Solution {
+    PathCounter::new(parse_output, &VisitPolicy::with_one_double_visit())
+        .unwrap()
+        .count()
+}
 
-        if visited < &self.max_visit_count
-            || (self.allow_one_above_max && self.get_max() < self.max_visit_count + 1)
-        {
-            *self.small_caves_visited.get_mut(id).unwrap() += 1;
-            return true;
-        }
+/// Lists every path instead of only counting them, following any `VisitPolicy` that
+/// passes its check just like `PathCounter`.
+fn enumerate_paths<'a>(
+    parse_output: &'a ParseOutput,
+    policy: &'a VisitPolicy,
+) -> Result<CavePathIter<'a>, String> {
+    policy.check(parse_output)?;
+    Ok(CavePathIter::new(
+        parse_output,
+        CavePath::new(parse_output.get_start_id(), policy),
+    ))
+}
 
-        return false;
-    }
+#[cfg(test)]
+mod tests {
+    extern crate test;
+    use super::*;
+    use crate::cave_system::is_small_cave;
+    use crate::parse_input::{parse, read_main, read_test};
+    use test::{black_box, Bencher};
 
-    pub fn get_max(&self) -> u8 {
-        self.small_caves_visited
-            .values()
-            .fold(0, |acc, num| max(acc, *num))
+    #[test]
+    pub fn test_part_1() {
+        let parse_output = parse(&read_test());
+        assert_eq!(part_1(&parse_output), 226);
     }
-}
 
-fn part_1(parse_output: &ParseOutput) -> Solution {
-    let mut valid_paths_count: u32 = 0;
-
-    let mut active_paths = vec![CavePath {
-        small_caves_visited: HashMap::new(),
-        allow_one_above_max: false,
-        max_visit_count: 1,
-        path: vec![parse_output.get_start_id()],
-    }];
+    #[test]
+    pub fn test_part_2() {
+        let parse_output = parse(&read_test());
+        assert_eq!(part_2(&parse_output), 3509);
+    }
 
-    while active_paths.len() > 0 {
-        active_paths = explore_cave(parse_output, active_paths);
+    #[test]
+    pub fn test_enumerate_paths() {
+        let parse_output = parse(&read_test());
+        assert_eq!(
+            enumerate_paths(&parse_output, &VisitPolicy::new())
+                .unwrap()
+                .count(),
+            226
+        );
+        assert_eq!(
+            enumerate_paths(&parse_output, &VisitPolicy::with_one_double_visit())
+                .unwrap()
+                .count(),
+            3509
+        );
 
-        active_paths = filter_path(active_paths, "dead");
-        let active_path_count_after_filtering_dead_ends = active_paths.len();
-        active_paths = filter_path(active_paths, "end");
-        valid_paths_count +=
-            (active_path_count_after_filtering_dead_ends - active_paths.len()) as u32;
+        assert_eq!(
+            get_sorted_paths(&get_small_example(), &VisitPolicy::new()),
+            vec![
+                "start,A,b,A,c,A,end",
+                "start,A,b,A,end",
+                "start,A,b,end",
+                "start,A,c,A,b,A,end",
+                "start,A,c,A,b,end",
+                "start,A,c,A,end",
+                "start,A,end",
+                "start,b,A,c,A,end",
+                "start,b,A,end",
+                "start,b,end",
+            ]
+        );
     }
 
-    valid_paths_count
-}
+    fn get_small_example() -> ParseOutput {
+        parse(&"start-A\nstart-b\nA-c\nA-b\nb-d\nA-end\nb-end".to_string())
+    }
 
-fn explore_cave(cave_system: &ParseOutput, active_paths: Vec<CavePath>) -> Vec<CavePath> {
-    let mut new_paths = Vec::new();
+    fn get_sorted_paths(parse_output: &ParseOutput, policy: &VisitPolicy) -> Vec<String> {
+        let mut paths: Vec<String> = enumerate_paths(parse_output, policy)
+            .unwrap()
+            .map(|p| p.join(","))
+            .collect();
+        paths.sort();
+        paths
+    }
 
-    for path in active_paths.iter() {
-        let cave_id = path.path.last().unwrap();
-        let adjacent_cave_ids = cave_system.get_adjacent_cave_ids(cave_id);
+    #[test]
+    pub fn test_policy_double_visit() {
+        let cave_system = get_small_example();
+        assert_eq!(
+            enumerate_paths(&cave_system, &VisitPolicy::with_one_double_visit())
+                .unwrap()
+                .count(),
+            36
+        );
 
-        for cave_id in &adjacent_cave_ids {
-            if cave_id == "start" {
-                continue;
-            }
+        let mut policy = VisitPolicy::new();
+        policy.visit_limits.insert("b".to_string(), 2);
+        let paths = get_sorted_paths(&cave_system, &policy);
+        assert_eq!(paths.len(), 30);
+        assert!(paths.contains(&"start,A,b,A,b,end".to_string()));
+        assert!(!paths.contains(&"start,A,c,A,c,A,end".to_string()));
+    }
 
-            let mut new_path = path.clone();
-            if is_small_cave(cave_id) {
-                let visit_successful = new_path.visit_small_cave(cave_id);
-                if !visit_successful {
-                    new_path.visit_small_cave(cave_id);
-                    new_path.path.push("dead".to_string());
-                    new_paths.push(new_path);
-                    continue;
-                }
-            }
+    #[test]
+    pub fn test_policy_extra_visit_budget() {
+        let cave_system = get_small_example();
+        let mut policy = VisitPolicy::new();
+        policy.extra_visits = 2;
 
-            new_path.path.push(cave_id.clone());
-            new_paths.push(new_path)
-        }
+        let paths = get_sorted_paths(&cave_system, &policy);
+        assert!(paths.contains(&"start,A,b,A,b,A,c,A,c,A,end".to_string()));
+        assert!(paths.contains(&"start,A,b,A,b,A,b,end".to_string()));
+        assert!(paths.len() > 36);
     }
 
-    new_paths
-}
-
-fn filter_path(active_paths: Vec<CavePath>, id: &str) -> Vec<CavePath> {
-    active_paths
-        .into_iter()
-        .filter(|p| p.path.last().unwrap() != id)
-        .collect()
-}
+    #[test]
+    pub fn test_policy_required_and_forbidden_caves() {
+        let cave_system = get_small_example();
 
-fn part_2(parse_output: &ParseOutput) -> Solution {
-    let mut valid_paths_count: u32 = 0;
+        let mut policy = VisitPolicy::new();
+        policy.required_caves.insert("c".to_string());
+        assert_eq!(
+            get_sorted_paths(&cave_system, &policy),
+            vec![
+                "start,A,b,A,c,A,end",
+                "start,A,c,A,b,A,end",
+                "start,A,c,A,b,end",
+                "start,A,c,A,end",
+                "start,b,A,c,A,end",
+            ]
+        );
 
-    let mut active_paths = vec![CavePath {
-        small_caves_visited: HashMap::new(),
-        allow_one_above_max: true,
-        max_visit_count: 1,
-        path: vec![parse_output.get_start_id()],
-    }];
+        let mut policy = VisitPolicy::new();
+        policy.forbidden_caves.insert("A".to_string());
+        assert_eq!(get_sorted_paths(&cave_system, &policy), vec!["start,b,end"]);
+    }
 
-    while active_paths.len() > 0 {
-        active_paths = explore_cave(parse_output, active_paths);
+    #[test]
+    pub fn test_policy_classifier() {
+        let cave_system = get_small_example();
 
-        active_paths = filter_path(active_paths, "dead");
-        let active_path_count_after_filtering_dead_ends = active_paths.len();
-        active_paths = filter_path(active_paths, "end");
-        valid_paths_count +=
-            (active_path_count_after_filtering_dead_ends - active_paths.len()) as u32;
+        // Limiting A as well means it can't be passed through twice anymore.
+        let mut policy = VisitPolicy::new();
+        policy.set_classifier(|id| is_small_cave(id) || id == "A");
+        assert_eq!(
+            get_sorted_paths(&cave_system, &policy),
+            vec![
+                "start,A,b,end",
+                "start,A,end",
+                "start,b,A,end",
+                "start,b,end"
+            ]
+        );
     }
 
-    valid_paths_count
-}
+    #[test]
+    pub fn test_counter_follows_policy() {
+        let mut policies = vec![VisitPolicy::new(), VisitPolicy::with_one_double_visit()];
+        let mut policy = VisitPolicy::new();
+        policy.visit_limits.insert("b".to_string(), 2);
+        policies.push(policy);
+        let mut policy = VisitPolicy::new();
+        policy.extra_visits = 2;
+        policy.visit_limits.insert("c".to_string(), 0);
+        policies.push(policy);
+        let mut policy = VisitPolicy::with_one_double_visit();
+        policy.required_caves.insert("c".to_string());
+        policy.required_caves.insert("start".to_string());
+        policies.push(policy);
+        let mut policy = VisitPolicy::new();
+        policy.required_caves.insert("missing".to_string());
+        policies.push(policy);
+        let mut policy = VisitPolicy::with_one_double_visit();
+        policy.forbidden_caves.insert("A".to_string());
+        policies.push(policy);
+        let mut policy = VisitPolicy::new();
+        policy.set_classifier(|id| is_small_cave(id) || id == "A");
+        policies.push(policy);
 
-#[cfg(test)]
-mod tests {
-    extern crate test;
-    use super::*;
-    use crate::parse_input::{parse, read_main, read_test};
-    use test::{black_box, Bencher};
+        for cave_system in [get_small_example(), parse(&read_test())] {
+            for policy in &policies {
+                assert_eq!(
+                    PathCounter::new(&cave_system, policy).unwrap().count(),
+                    enumerate_paths(&cave_system, policy).unwrap().count() as u64
+                );
+            }
+        }
 
-    #[test]
-    pub fn test_part_1() {
-        let parse_output = parse(&read_test());
-        assert_eq!(part_1(&parse_output), 226);
+        let cave_system = parse(&"start-A\nA-B\nB-end".to_string());
+        assert!(PathCounter::new(&cave_system, &VisitPolicy::new()).is_err());
     }
 
     #[test]
-    pub fn test_part_2() {
-        let parse_output = parse(&read_test());
-        assert_eq!(part_2(&parse_output), 3509);
+    pub fn test_policy_rejects_adjacent_unlimited_caves() {
+        let cave_system = parse(&"start-A\nA-B\nB-end".to_string());
+        assert_eq!(
+            enumerate_paths(&cave_system, &VisitPolicy::new()).err(),
+            Some(
+                "caves A and B are adjacent and both unlimited, paths through them never end"
+                    .to_string()
+            )
+        );
+
+        let mut policy = VisitPolicy::new();
+        policy.set_classifier(|id| id != "b");
+        assert!(enumerate_paths(&get_small_example(), &policy).is_ok());
+        policy.set_classifier(|id| id != "A" && id != "b");
+        assert!(enumerate_paths(&get_small_example(), &policy).is_err());
+
+        policy.forbidden_caves.insert("b".to_string());
+        assert_eq!(
+            get_sorted_paths(&get_small_example(), &policy),
+            vec!["start,A,c,A,end", "start,A,end"]
+        );
     }
 
     #[bench]
@@ -173,4 +283,18 @@ mod tests {
             assert_eq!(part_2(black_box(&parse_output)), 130493);
         });
     }
+
+    #[bench]
+    fn bench_enumerate_paths(b: &mut Bencher) {
+        let parse_output = parse(&read_main());
+        let policy = VisitPolicy::with_one_double_visit();
+        b.iter(|| {
+            assert_eq!(
+                enumerate_paths(black_box(&parse_output), &policy)
+                    .unwrap()
+                    .count(),
+                130493
+            );
+        });
+    }
 }
@@ -1,4 +1,5 @@
 use crate::cave_system::CaveSystem;
+use crate::graph_format::{from_dot, from_json};
 
 pub type ParseOutput = CaveSystem;
 
@@ -24,3 +25,15 @@ pub fn parse(file: &String) -> ParseOutput {
 
     out
 }
+
+/// Reads a cave system from a puzzle input, or from a `.dot` or `.json` export.
+pub fn parse_file(file_name: &str) -> Result<ParseOutput, String> {
+    let file = read_file(file_name);
+    if file_name.ends_with(".dot") {
+        from_dot(&file)
+    } else if file_name.ends_with(".json") {
+        from_json(&file)
+    } else {
+        Ok(parse(&file))
+    }
+}
@@ -0,0 +1,107 @@
+use crate::cave_system::{CaveIndex, CaveSystem};
+use crate::visit_policy::VisitPolicy;
+use std::collections::HashMap;
+
+/// (cave, visits of every limited cave so far, extra visits used, required caves visited
+/// as a bitmask)
+type CountKey = (CaveIndex, Vec<u8>, u8, u64);
+
+/// Counts paths through a cave system without building them, following the same
+/// `VisitPolicy` as `CavePath`. The visits of every limited cave are part of the state, which
+/// is still small enough to remember how many paths lead from it to the end.
+pub struct PathCounter {
+    start: CaveIndex,
+    end: CaveIndex,
+    adjacent_caves: Vec<Vec<CaveIndex>>,
+    /// How often each cave may be entered, `None` if it isn't limited.
+    visit_limits: Vec<Option<u8>>,
+    is_forbidden: Vec<bool>,
+    extra_visits: u8,
+    /// Required caves as a bitmask, `None` if one of them isn't in the cave system.
+    required: Option<u64>,
+    memo: HashMap<CountKey, u64>,
+}
+
+impl PathCounter {
+    pub fn new(cave_system: &CaveSystem, policy: &VisitPolicy) -> Result<PathCounter, String> {
+        if cave_system.len() > 64 {
+            return Err("can only count paths in cave systems with up to 64 caves".to_string());
+        }
+        policy.check(cave_system)?;
+
+        let ids = cave_system.get_cave_ids();
+        Ok(PathCounter {
+            start: cave_system.get_start(),
+            end: cave_system.get_end(),
+            adjacent_caves: (0..cave_system.len())
+                .map(|i| cave_system.get_adjacent_caves(i).clone())
+                .collect(),
+            visit_limits: ids
+                .iter()
+                .map(|id| match policy.is_limited(id) {
+                    true => Some(policy.get_visit_limit(id)),
+                    false => None,
+                })
+                .collect(),
+            is_forbidden: ids.iter().map(|id| policy.is_forbidden(id)).collect(),
+            extra_visits: policy.extra_visits,
+            required: policy.required_caves.iter().try_fold(0, |mask, id| {
+                cave_system.get_cave_index(id).map(|i| mask | 1 << i)
+            }),
+            memo: HashMap::new(),
+        })
+    }
+
+    /// Paths from start to end the policy allows.
+    pub fn count(&mut self) -> u64 {
+        let required = match self.required {
+            Some(required) => required,
+            None => return 0,
+        };
+        let visits = vec![0; self.visit_limits.len()];
+        self.count_from(self.start, visits, 0, required & !(1 << self.start))
+    }
+
+    fn count_from(
+        &mut self,
+        cave: CaveIndex,
+        visits: Vec<u8>,
+        extra_used: u8,
+        missing: u64,
+    ) -> u64 {
+        if cave == self.end {
+            return (missing == 0) as u64;
+        }
+        let key = (cave, visits, extra_used, missing);
+        if let Some(count) = self.memo.get(&key) {
+            return *count;
+        }
+        let (_, visits, _, _) = &key;
+
+        let mut count = 0;
+        for i in 0..self.adjacent_caves[cave].len() {
+            let next = self.adjacent_caves[cave][i];
+            if next == self.start || self.is_forbidden[next] {
+                continue;
+            }
+            let missing = missing & !(1 << next);
+
+            match self.visit_limits[next] {
+                None => count += self.count_from(next, visits.clone(), extra_used, missing),
+                Some(limit) => {
+                    let extra_used = match visits[next] < limit {
+                        true => extra_used,
+                        false if extra_used < self.extra_visits => extra_used + 1,
+                        false => continue,
+                    };
+                    let mut visits = visits.clone();
+                    visits[next] += 1;
+                    count += self.count_from(next, visits, extra_used, missing);
+                }
+            }
+        }
+
+        self.memo.insert(key, count);
+        count
+    }
+}
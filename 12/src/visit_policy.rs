@@ -0,0 +1,84 @@
+use crate::cave_system::{is_small_cave, CaveId, CaveSystem};
+use std::collections::{HashMap, HashSet};
+
+/// Decides which caves a path may enter and how often.
+///
+/// Only caves the classifier marks as limited are counted, by default those are the small
+/// (lowercase) ones. A limited cave can be entered `max_visit_count` times unless
+/// `visit_limits` says otherwise, and on top of that the whole path may go over those
+/// limits `extra_visits` times.
+pub struct VisitPolicy {
+    pub max_visit_count: u8,
+    pub visit_limits: HashMap<CaveId, u8>,
+    pub extra_visits: u8,
+    pub required_caves: HashSet<CaveId>,
+    pub forbidden_caves: HashSet<CaveId>,
+    is_limited: Box<dyn Fn(&CaveId) -> bool>,
+}
+
+impl Default for VisitPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VisitPolicy {
+    /// Part 1: every small cave at most once.
+    pub fn new() -> VisitPolicy {
+        VisitPolicy {
+            max_visit_count: 1,
+            visit_limits: HashMap::new(),
+            extra_visits: 0,
+            required_caves: HashSet::new(),
+            forbidden_caves: HashSet::new(),
+            is_limited: Box::new(is_small_cave),
+        }
+    }
+
+    /// Part 2: every small cave at most once, except for a single one that may be
+    /// visited twice.
+    pub fn with_one_double_visit() -> VisitPolicy {
+        VisitPolicy {
+            extra_visits: 1,
+            ..VisitPolicy::new()
+        }
+    }
+
+    pub fn set_classifier(&mut self, is_limited: impl Fn(&CaveId) -> bool + 'static) {
+        self.is_limited = Box::new(is_limited);
+    }
+
+    pub fn is_limited(&self, id: &CaveId) -> bool {
+        (self.is_limited)(id)
+    }
+
+    pub fn is_forbidden(&self, id: &CaveId) -> bool {
+        self.forbidden_caves.contains(id)
+    }
+
+    /// Paths could go back and forth between two adjacent caves that aren't limited
+    /// forever, so such a pair makes the policy unusable for that cave system. Start and
+    /// end don't count, paths never return to the one and stop at the other.
+    pub fn check(&self, cave_system: &CaveSystem) -> Result<(), String> {
+        let is_unlimited = |id: &CaveId| {
+            *id != cave_system.get_start_id()
+                && *id != cave_system.get_end_id()
+                && !self.is_forbidden(id)
+                && !self.is_limited(id)
+        };
+        for (a, b) in cave_system.get_connections() {
+            let (a, b) = (cave_system.get_cave_id(a), cave_system.get_cave_id(b));
+            if is_unlimited(a) && is_unlimited(b) {
+                return Err(format!(
+                    "caves {} and {} are adjacent and both unlimited, paths through them never end",
+                    a, b
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_visit_limit(&self, id: &CaveId) -> u8 {
+        *self.visit_limits.get(id).unwrap_or(&self.max_visit_count)
+    }
+}
@@ -1,10 +1,12 @@
 #![feature(test)]
 
-use crate::parse_input::{parse, read_main, FoldList, ParseOutput, Point};
+use crate::parse_input::{parse, read_main, ParseOutput};
+use crate::sheet::Sheet;
+use aoc_ocr::{read_points, OcrError};
 use std::cmp::max;
-use std::collections::HashSet;
 
 pub mod parse_input;
+pub mod sheet;
 
 type Solution = u32;
 
@@ -12,60 +14,35 @@ fn main() {
     let parse_output = parse(&read_main());
     println!("Solution to part 1 is {}", part_1(&parse_output));
     println!("Solution to part 2 is \n{}", part_2(&parse_output));
+    match read_code(&parse_output) {
+        Ok(code) => println!("which reads {}", code),
+        Err(err) => println!("which can't be read: {}", err),
+    }
 }
 
 fn part_1(parse_output: &ParseOutput) -> Solution {
-    let (mut points, _, _, first_fold) = parse_output.clone();
-    let mut unique_point_hash_set: HashSet<Point> = HashSet::new();
-
-    let y_folds = if first_fold.0 > 0 {
-        vec![first_fold.0]
-    } else {
-        vec![]
-    };
-
-    let x_folds = if first_fold.1 > 0 {
-        vec![first_fold.1]
-    } else {
-        vec![]
-    };
-
-    for point in points.drain(..) {
-        unique_point_hash_set.insert(fold_point(point, &y_folds, &x_folds));
-    }
-    unique_point_hash_set.len() as u32
+    let (points, folds) = parse_output;
+    Sheet::new(points).fold_all(&folds[..1]).get_dot_count() as u32
 }
 
-fn fold_point(point: Point, folds_y: &FoldList, folds_x: &FoldList) -> Point {
-    let mut y = point.0;
-    let mut x = point.1;
-
-    for fold_line in folds_y {
-        if &y < fold_line {
-            continue;
-        }
-
-        y = (2 * fold_line) - y;
-    }
-
-    for fold_line in folds_x {
-        if &x < fold_line {
-            continue;
-        }
-
-        x = (2 * fold_line) - x;
-    }
+fn fold_all(parse_output: &ParseOutput) -> Sheet {
+    let (points, folds) = parse_output;
+    Sheet::new(points).fold_all(folds)
+}
 
-    (y, x)
+/// The code the dots of the folded paper spell.
+fn read_code(parse_output: &ParseOutput) -> Result<String, OcrError> {
+    let points: Vec<(i32, i32)> = fold_all(parse_output)
+        .get_dots()
+        .iter()
+        .map(|(y, x)| (*x, *y))
+        .collect();
+    read_points(&points)
 }
 
 fn part_2(parse_output: &ParseOutput) -> String {
-    let (mut points, y_folds, x_folds, _) = parse_output.clone();
-    let mut unique_point_hash_set: HashSet<Point> = HashSet::new();
-
-    for point in points.drain(0..) {
-        unique_point_hash_set.insert(fold_point(point, &y_folds, &x_folds));
-    }
+    let sheet = fold_all(parse_output);
+    let unique_point_hash_set = sheet.get_dots();
 
     let maximum = unique_point_hash_set
         .iter()
@@ -113,6 +90,15 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_read_code() {
+        assert_eq!(read_code(&parse(&read_main())), Ok("PCPHARKL".to_string()));
+        assert_eq!(
+            read_code(&parse(&read_test())),
+            Err(OcrError::UnknownHeight(5))
+        );
+    }
+
     #[bench]
     fn bench_parse(b: &mut Bencher) {
         let file = read_main();
@@ -1,7 +1,8 @@
+use crate::sheet::Fold;
+
 pub type Point = (i32, i32);
-pub type FoldList = Vec<i32>;
 
-pub type ParseOutput = (Vec<Point>, FoldList, FoldList, Point);
+pub type ParseOutput = (Vec<Point>, Vec<Fold>);
 
 pub fn read_main() -> String {
     read_file("src/13.txt")
@@ -16,9 +17,7 @@ pub fn read_file(file_name: &str) -> String {
 }
 
 pub fn parse(file: &String) -> ParseOutput {
-    let mut x_folds: FoldList = Vec::new();
-    let mut y_folds: FoldList = Vec::new();
-    let mut first_fold: Option<Point> = None;
+    let mut folds = Vec::new();
     let mut points = Vec::new();
 
     let (points_to_parse, folds_to_parse) = file.split_once("\n\n").unwrap();
@@ -28,20 +27,15 @@ pub fn parse(file: &String) -> ParseOutput {
         points.push((y.parse().unwrap(), x.parse().unwrap()));
     }
 
-    for fold in folds_to_parse.split("\n") {
-        if let Some((_, x_fold)) = fold.split_once("x=") {
-            x_folds.push(x_fold.parse().unwrap());
-            if let None = first_fold {
-                first_fold = Some((0, x_folds.last().unwrap().clone()));
-            }
-        }
-        if let Some((_, y_fold)) = fold.split_once("y=") {
-            y_folds.push(y_fold.parse().unwrap());
-            if let None = first_fold {
-                first_fold = Some((y_folds.last().unwrap().clone(), 0));
-            }
-        }
+    for fold in folds_to_parse.lines() {
+        let (axis, line) = fold.split_once("=").unwrap();
+        let line = line.parse().unwrap();
+        folds.push(match axis.chars().last().unwrap() {
+            'x' => Fold::AlongX(line),
+            'y' => Fold::AlongY(line),
+            _ => panic!("unknown fold axis in {}", fold),
+        });
     }
 
-    (points, y_folds, x_folds, first_fold.unwrap())
+    (points, folds)
 }
@@ -0,0 +1,180 @@
+use crate::parse_input::Point;
+use std::collections::HashSet;
+
+/// A fold instruction, `AlongY` folds the bottom part up and `AlongX` folds the right part
+/// to the left.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fold {
+    AlongX(i32),
+    AlongY(i32),
+}
+
+/// A sheet of transparent paper with its dots stored as `(y, x)`. The sheet always starts
+/// at `(0, 0)`, so when the part folded over is larger than the part it lands on the
+/// dots are shifted to keep their coordinates positive.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sheet {
+    dots: HashSet<Point>,
+    height: i32,
+    width: i32,
+}
+
+/// The length of a sheet `size` long once folded along `line`, and how far what is left
+/// of the line moves to make room for the longer part folded over it.
+fn fold_size(line: i32, size: i32) -> (i32, i32) {
+    match line < size {
+        true => {
+            let below = size - 1 - line;
+            (line.max(below), (below - line).max(0))
+        }
+        false => (size, 0),
+    }
+}
+
+/// Where `i` ends up after folding along `line`, `None` for the crease itself.
+fn fold_coordinate(i: i32, line: i32, shift: i32) -> Option<i32> {
+    match i {
+        i if i < line => Some(i + shift),
+        i if i > line => Some(2 * line - i + shift),
+        _ => None,
+    }
+}
+
+impl Sheet {
+    /// The smallest sheet that holds all of the dots.
+    pub fn new(dots: &[Point]) -> Self {
+        Sheet {
+            dots: dots.iter().cloned().collect(),
+            height: dots.iter().map(|(y, _)| y + 1).max().unwrap_or(0),
+            width: dots.iter().map(|(_, x)| x + 1).max().unwrap_or(0),
+        }
+    }
+
+    pub fn get_dots(&self) -> &HashSet<Point> {
+        &self.dots
+    }
+
+    pub fn get_dot_count(&self) -> usize {
+        self.dots.len()
+    }
+
+    /// `(height, width)`
+    pub fn get_size(&self) -> (i32, i32) {
+        (self.height, self.width)
+    }
+
+    /// Folds along the line of `fold`. The line itself disappears into the crease and
+    /// takes any dots on it along, which the puzzle never has.
+    pub fn fold(&self, fold: &Fold) -> Sheet {
+        let (mut height, mut width) = (self.height, self.width);
+        let dots = match fold {
+            Fold::AlongY(line) => {
+                let shift;
+                (height, shift) = fold_size(*line, self.height);
+                self.dots
+                    .iter()
+                    .filter_map(|(y, x)| Some((fold_coordinate(*y, *line, shift)?, *x)))
+                    .collect()
+            }
+            Fold::AlongX(line) => {
+                let shift;
+                (width, shift) = fold_size(*line, self.width);
+                self.dots
+                    .iter()
+                    .filter_map(|(y, x)| Some((*y, fold_coordinate(*x, *line, shift)?)))
+                    .collect()
+            }
+        };
+
+        Sheet {
+            dots,
+            height,
+            width,
+        }
+    }
+
+    /// The sheet after each of the folds in order. Stopping after `n` folds is
+    /// `fold_all(&folds[..n])`.
+    pub fn fold_all(&self, folds: &[Fold]) -> Sheet {
+        folds
+            .iter()
+            .fold(self.clone(), |sheet, fold| sheet.fold(fold))
+    }
+
+    /// The sheet after none, one, two and so on up to all of the folds.
+    pub fn get_snapshots(&self, folds: &[Fold]) -> Vec<Sheet> {
+        let mut snapshots = vec![self.clone()];
+        for fold in folds {
+            snapshots.push(snapshots.last().unwrap().fold(fold));
+        }
+        snapshots
+    }
+
+    /// One line per row with `dot` for the dots and `empty` for the rest of the sheet.
+    pub fn render(&self, dot: char, empty: char) -> String {
+        let mut rendering = String::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                rendering.push(match self.dots.contains(&(y, x)) {
+                    true => dot,
+                    false => empty,
+                });
+            }
+            rendering.push('\n');
+        }
+        rendering
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input::{parse, read_test};
+
+    #[test]
+    fn snapshots_test() {
+        let (dots, folds) = parse(&read_test());
+        let snapshots = Sheet::new(&dots).get_snapshots(&folds);
+
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(
+            snapshots
+                .iter()
+                .map(Sheet::get_dot_count)
+                .collect::<Vec<_>>(),
+            vec![18, 17, 16]
+        );
+        assert_eq!(snapshots[1], Sheet::new(&dots).fold_all(&folds[..1]));
+        assert_eq!(
+            snapshots[1].render('#', '.'),
+            "#.##..#..#.\n\
+             #...#......\n\
+             ......#...#\n\
+             #...#......\n\
+             .#.#..#.###\n\
+             ...........\n\
+             ...........\n"
+        );
+        assert_eq!(
+            snapshots[2].render('#', '.'),
+            "#####\n#...#\n#...#\n#...#\n#####\n.....\n.....\n"
+        );
+    }
+
+    #[test]
+    fn uneven_fold_test() {
+        // The part below the fold is longer, so it sticks out above the top once folded.
+        let sheet = Sheet::new(&[(0, 0), (1, 1), (5, 0)]);
+        let folded = sheet.fold(&Fold::AlongY(1));
+        assert_eq!(folded.get_size(), (4, 2));
+        assert_eq!(folded.render('#', '.'), "#.\n..\n..\n#.\n");
+
+        // The crease is gone and so is the dot that was on it.
+        let folded = sheet.fold(&Fold::AlongX(1));
+        assert_eq!(folded.get_size(), (6, 1));
+        assert_eq!(folded.get_dot_count(), 2);
+
+        // Folding along a line outside of the sheet does nothing.
+        assert_eq!(sheet.fold(&Fold::AlongY(10)), sheet);
+    }
+}
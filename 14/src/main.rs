@@ -5,18 +5,51 @@
 use crate::parse_input::{
     parse, read_main, BaseFrequencyMap, InstructionFrequencyMap, Instructions, ParseOutput, Polymer,
 };
+use crate::transition::PairTransition;
 use std::cmp::{max, min};
 
 pub mod parse_input;
+pub mod polymer;
+pub mod transition;
 
 type Solution = i64;
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("jump") {
+        let steps = args.get(2).map_or(40, |steps| steps.parse().unwrap());
+        let modulus = args.get(3).map(|modulus| modulus.parse().unwrap());
+        print_jump(&parse(&read_main()), steps, modulus);
+        return;
+    }
+
     let parse_output = parse(&read_main());
     println!("Solution to part 1 is {}", part_1(&parse_output));
     println!("Solution to part 2 is {}", part_2(&parse_output));
 }
 
+/// Element counts after `steps`, exact if they fit and modulo `modulus` if given.
+fn print_jump(parse_output: &ParseOutput, steps: u64, modulus: Option<u64>) {
+    let transition = PairTransition::new(parse_output);
+    let counts: Vec<(char, u128)> = match modulus {
+        Some(modulus) => transition
+            .count_elements_modulo(parse_output, steps, modulus)
+            .into_iter()
+            .map(|(element, count)| (element, count as u128))
+            .collect(),
+        None => match transition.count_elements(parse_output, steps) {
+            Some(counts) => counts,
+            None => {
+                println!("Counts after {} steps don't fit, give a modulus", steps);
+                return;
+            }
+        },
+    };
+    for (element, count) in counts {
+        println!("{}: {}", element, count);
+    }
+}
+
 fn part_1(parse_output: &ParseOutput) -> Solution {
     build_polymer(parse_output, 10)
 }
@@ -75,6 +108,7 @@ mod tests {
     extern crate test;
     use super::*;
     use crate::parse_input::{parse, read_main, read_test};
+    use crate::polymer::PolymerEngine;
     use test::{black_box, Bencher};
 
     #[test]
@@ -89,6 +123,54 @@ mod tests {
         assert_eq!(part_2(&parse_output), 2188189693529);
     }
 
+    #[test]
+    pub fn test_polymer_engine() {
+        let parse_output = parse(&read_main());
+        let mut engine = PolymerEngine::parse(&read_main()).unwrap();
+        for steps in 0..=40 {
+            assert_eq!(
+                engine.get_spread() as i64,
+                build_polymer(&parse_output, steps)
+            );
+            engine.step();
+        }
+    }
+
+    #[test]
+    pub fn test_transition_matches_build_polymer() {
+        for file in [read_test(), read_main()] {
+            let parse_output = parse(&file);
+            let transition = PairTransition::new(&parse_output);
+            for steps in 0..=20 {
+                let counts = transition.count_elements(&parse_output, steps).unwrap();
+                let most = counts.iter().map(|(_, count)| *count).max().unwrap();
+                let least = counts.iter().map(|(_, count)| *count).min().unwrap();
+                assert_eq!(
+                    (most - least) as i64,
+                    build_polymer(&parse_output, steps as u32)
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_transition_with_element_only_in_template() {
+        // No rule mentions Z, so it stays once in the polymer and is the least common.
+        let file = read_test().replacen("NNCB", "NNCBZ", 1);
+        let parse_output = parse(&file);
+        let transition = PairTransition::new(&parse_output);
+        for steps in 0..=10 {
+            let counts = transition.count_elements(&parse_output, steps).unwrap();
+            assert!(counts.contains(&('Z', 1)));
+            let most = counts.iter().map(|(_, count)| *count).max().unwrap();
+            let least = counts.iter().map(|(_, count)| *count).min().unwrap();
+            assert_eq!(
+                (most - least) as i64,
+                build_polymer(&parse_output, steps as u32)
+            );
+        }
+    }
+
     #[bench]
     fn bench_parse(b: &mut Bencher) {
         let file = read_main();
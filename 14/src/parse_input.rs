@@ -77,7 +77,7 @@ pub fn parse(file: &String) -> ParseOutput {
                 *instruction_freq += 1;
             }
         }
-        *base_frequency.get_mut(&current_char).unwrap() += 1;
+        *base_frequency.entry(current_char).or_insert(0) += 1;
         last_char_option = Some(current_char);
     }
 
@@ -0,0 +1,335 @@
+/// An element interned to its index in the `Alphabet`.
+pub type Element = u8;
+
+/// Polymers longer than this aren't built out as strings.
+pub const MAX_POLYMER_LENGTH: usize = 1 << 20;
+
+/// The elements known so far, each one stands for its index in `elements`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Alphabet {
+    elements: Vec<char>,
+}
+
+impl Alphabet {
+    pub fn new(elements: &str) -> Self {
+        let mut alphabet = Alphabet::default();
+        for element in elements.chars() {
+            alphabet.intern(element);
+        }
+        alphabet
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    pub fn get_index(&self, element: char) -> Option<Element> {
+        self.elements
+            .iter()
+            .position(|known| *known == element)
+            .map(|index| index as Element)
+    }
+
+    pub fn get_element(&self, index: Element) -> char {
+        self.elements[index as usize]
+    }
+
+    /// The index of `element`, adding it if it is new. There is room for 256 elements.
+    pub fn intern(&mut self, element: char) -> Element {
+        if let Some(index) = self.get_index(element) {
+            return index;
+        }
+        assert!(
+            self.elements.len() <= Element::MAX as usize,
+            "alphabet is full"
+        );
+        self.elements.push(element);
+        (self.elements.len() - 1) as Element
+    }
+}
+
+/// Pair insertion rules. `insertions[a][b]` is what goes between `a` and `b`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RuleSet {
+    alphabet: Alphabet,
+    insertions: Vec<Vec<Option<Element>>>,
+}
+
+impl RuleSet {
+    pub fn new(alphabet: Alphabet) -> Self {
+        let size = alphabet.len();
+        RuleSet {
+            alphabet,
+            insertions: vec![vec![None; size]; size],
+        }
+    }
+
+    /// Reads rules like `CH -> B`, one per line.
+    pub fn parse(rules: &str) -> Result<Self, String> {
+        let mut rule_set = RuleSet::default();
+        for line in rules.lines().filter(|line| !line.is_empty()) {
+            let (pair, insertion) = line
+                .split_once(" -> ")
+                .ok_or(format!("no ` -> ` in rule `{}`", line))?;
+            let pair: Vec<char> = pair.chars().collect();
+            let insertion: Vec<char> = insertion.chars().collect();
+            match (pair.as_slice(), insertion.as_slice()) {
+                ([a, b], [c]) => rule_set.add_rule(*a, *b, *c),
+                _ => return Err(format!("malformed rule `{}`", line)),
+            };
+        }
+        Ok(rule_set)
+    }
+
+    pub fn get_alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Interns an element the rules don't mention yet, such as one from a template.
+    pub fn intern(&mut self, element: char) -> Element {
+        let index = self.alphabet.intern(element);
+        let size = self.alphabet.len();
+        if self.insertions.len() < size {
+            for row in self.insertions.iter_mut() {
+                row.resize(size, None);
+            }
+            self.insertions.resize(size, vec![None; size]);
+        }
+        index
+    }
+
+    /// Adds or replaces the rule inserting `insertion` between `a` and `b`.
+    pub fn add_rule(&mut self, a: char, b: char, insertion: char) {
+        let (a, b, insertion) = (self.intern(a), self.intern(b), self.intern(insertion));
+        self.insertions[a as usize][b as usize] = Some(insertion);
+    }
+
+    pub fn remove_rule(&mut self, a: char, b: char) {
+        if let (Some(a), Some(b)) = (self.alphabet.get_index(a), self.alphabet.get_index(b)) {
+            self.insertions[a as usize][b as usize] = None;
+        }
+    }
+
+    pub fn get_insertion(&self, a: Element, b: Element) -> Option<Element> {
+        self.insertions[a as usize][b as usize]
+    }
+}
+
+/// Grows a polymer from its template by counting its pairs, so a step takes the same time
+/// however long the polymer is. The element counts of every step are kept.
+///
+/// Counts are `u64`, which lasts for about 60 steps before overflowing and panicking.
+#[derive(Clone, Debug)]
+pub struct PolymerEngine {
+    rules: RuleSet,
+    template: Vec<Element>,
+    pair_counts: Vec<Vec<u64>>,
+    history: Vec<Vec<u64>>,
+}
+
+impl PolymerEngine {
+    pub fn new(mut rules: RuleSet, template: &str) -> Self {
+        let template: Vec<Element> = template.chars().map(|c| rules.intern(c)).collect();
+        let size = rules.get_alphabet().len();
+
+        let mut pair_counts = vec![vec![0; size]; size];
+        for pair in template.windows(2) {
+            pair_counts[pair[0] as usize][pair[1] as usize] += 1;
+        }
+        let mut element_counts = vec![0; size];
+        for element in template.iter() {
+            element_counts[*element as usize] += 1;
+        }
+
+        PolymerEngine {
+            rules,
+            template,
+            pair_counts,
+            history: vec![element_counts],
+        }
+    }
+
+    /// Reads the template, an empty line and the rules.
+    pub fn parse(file: &str) -> Result<Self, String> {
+        let (template, rules) = file
+            .split_once("\n\n")
+            .ok_or("no empty line after the template")?;
+        Ok(PolymerEngine::new(RuleSet::parse(rules)?, template.trim()))
+    }
+
+    pub fn get_rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// How many steps have been taken.
+    pub fn get_step(&self) -> usize {
+        self.history.len() - 1
+    }
+
+    pub fn step(&mut self) {
+        let size = self.pair_counts.len();
+        let mut pair_counts = vec![vec![0u64; size]; size];
+        let mut element_counts = self.history.last().unwrap().clone();
+        for a in 0..size {
+            for b in 0..size {
+                let count = self.pair_counts[a][b];
+                if count == 0 {
+                    continue;
+                }
+                match self.rules.get_insertion(a as Element, b as Element) {
+                    Some(c) => {
+                        let c = c as usize;
+                        add(&mut pair_counts[a][c], count);
+                        add(&mut pair_counts[c][b], count);
+                        add(&mut element_counts[c], count);
+                    }
+                    None => add(&mut pair_counts[a][b], count),
+                }
+            }
+        }
+        self.pair_counts = pair_counts;
+        self.history.push(element_counts);
+    }
+
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Element counts after `step`, which must have been taken, of every element in the
+    /// polymer.
+    pub fn get_element_counts(&self, step: usize) -> Vec<(char, u64)> {
+        let alphabet = self.rules.get_alphabet();
+        self.history[step]
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(element, count)| (alphabet.get_element(element as Element), *count))
+            .collect()
+    }
+
+    /// Element counts of the polymer after each step so far, indexed by `Element`.
+    pub fn get_history(&self) -> &Vec<Vec<u64>> {
+        &self.history
+    }
+
+    pub fn get_count(&self, element: char) -> u64 {
+        match self.rules.get_alphabet().get_index(element) {
+            Some(element) => self.history.last().unwrap()[element as usize],
+            None => 0,
+        }
+    }
+
+    pub fn get_length(&self) -> u64 {
+        self.history.last().unwrap().iter().sum()
+    }
+
+    /// The count of the most common element minus that of the least common one. Every
+    /// element the rules or the template know counts, so one the rules only insert later
+    /// is the least common with a count of zero until then.
+    pub fn get_spread(&self) -> u64 {
+        let counts = self.history.last().unwrap();
+        let most = counts.iter().max().cloned().unwrap_or(0);
+        let least = counts.iter().min().cloned().unwrap_or(0);
+        most - least
+    }
+
+    /// The polymer after `steps` steps written out, `None` if it would be longer than
+    /// `MAX_POLYMER_LENGTH`.
+    pub fn build_string(&self, steps: usize) -> Option<String> {
+        let mut polymer = self.template.clone();
+        for _ in 0..steps {
+            if polymer.len() > MAX_POLYMER_LENGTH {
+                return None;
+            }
+            let mut next = Vec::with_capacity(polymer.len() * 2);
+            for pair in polymer.windows(2) {
+                next.push(pair[0]);
+                if let Some(insertion) = self.rules.get_insertion(pair[0], pair[1]) {
+                    next.push(insertion);
+                }
+            }
+            next.extend(polymer.last());
+            polymer = next;
+        }
+        if polymer.len() > MAX_POLYMER_LENGTH {
+            return None;
+        }
+        let alphabet = self.rules.get_alphabet();
+        Some(polymer.iter().map(|e| alphabet.get_element(*e)).collect())
+    }
+}
+
+fn add(count: &mut u64, change: u64) {
+    *count = count
+        .checked_add(change)
+        .expect("polymer counts overflowed u64");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input::read_test;
+
+    #[test]
+    fn small_steps_test() {
+        let mut engine = PolymerEngine::parse(&read_test()).unwrap();
+        assert_eq!(engine.build_string(0), Some("NNCB".to_string()));
+        assert_eq!(engine.build_string(2), Some("NBCCNBBBCBHCB".to_string()));
+        assert_eq!(
+            engine.build_string(4),
+            Some("NBBNBNBBCCNBCNCCNBBNBBNBBBNBBNBBCBHCBHHNHCBBCBHCB".to_string())
+        );
+
+        // H isn't in the template yet and counts as zero.
+        assert_eq!(engine.get_spread(), 2);
+
+        engine.run(10);
+        assert_eq!(engine.get_step(), 10);
+        assert_eq!(engine.get_length(), 3073);
+        assert_eq!(engine.get_count('B'), 1749);
+        assert_eq!(engine.get_count('H'), 161);
+        assert_eq!(engine.get_count('X'), 0);
+        assert_eq!(engine.get_spread(), 1588);
+
+        // The history agrees with writing the polymer out.
+        for step in 0..=10 {
+            let polymer = engine.build_string(step).unwrap();
+            for (element, count) in engine.get_element_counts(step) {
+                let written = polymer.chars().filter(|c| *c == element).count();
+                assert_eq!(written as u64, count);
+            }
+            assert_eq!(
+                engine.get_history()[step].iter().sum::<u64>(),
+                polymer.len() as u64
+            );
+        }
+        assert_eq!(engine.build_string(40), None);
+    }
+
+    #[test]
+    fn custom_rules_test() {
+        // Without rules for the pairs it makes, an insertion only happens once.
+        let mut rules = RuleSet::new(Alphabet::new("ab"));
+        rules.add_rule('a', 'b', 'c');
+        let mut engine = PolymerEngine::new(rules.clone(), "abz");
+        engine.run(3);
+        assert_eq!(engine.build_string(3), Some("acbz".to_string()));
+        assert_eq!(engine.get_element_counts(3).len(), 4);
+        assert_eq!(engine.get_spread(), 0);
+        assert_eq!(engine.get_rules().get_alphabet().get_index('z'), Some(3));
+
+        rules.add_rule('a', 'c', 'a');
+        rules.remove_rule('a', 'b');
+        let engine = PolymerEngine::new(rules, "ab");
+        assert_eq!(engine.build_string(3), Some("ab".to_string()));
+
+        assert!(RuleSet::parse("AB -> C\nAB - C").is_err());
+        assert!(RuleSet::parse("ABC -> D").is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::parse_input::{BaseFrequencyMap, InstructionFrequencyMap, ParseOutput};
+
+type Matrix = Vec<Vec<u128>>;
+
+/// One step of `build_step` as a linear map. The state is the count of every pair
+/// followed by the count of every element, and a step multiplies it by `matrix`, so
+/// `steps` steps are a single multiplication by the `steps`-th power of `matrix`.
+pub struct PairTransition {
+    pairs: Vec<(char, char)>,
+    elements: Vec<char>,
+    matrix: Matrix,
+}
+
+impl PairTransition {
+    /// Elements come from the rules and the template, which can hold some no rule mentions.
+    pub fn new(parse_output: &ParseOutput) -> Self {
+        let (instructions, _, base_frequency) = parse_output;
+        let mut pairs: Vec<(char, char)> = instructions.keys().cloned().collect();
+        pairs.sort();
+        let mut elements: Vec<char> = instructions
+            .iter()
+            .flat_map(|(pair, instruction)| {
+                [pair.0, pair.1]
+                    .into_iter()
+                    .chain(instruction.base_count_change.keys().cloned())
+            })
+            .chain(base_frequency.keys().cloned())
+            .collect();
+        elements.sort();
+        elements.dedup();
+
+        let size = pairs.len() + elements.len();
+        let mut matrix: Vec<Vec<i64>> = (0..size)
+            .map(|i| (0..size).map(|j| (i == j) as i64).collect())
+            .collect();
+        for (from, pair) in pairs.iter().enumerate() {
+            let instruction = &instructions[pair];
+            for (to, change) in instruction.count_change.iter() {
+                if let Ok(to) = pairs.binary_search(to) {
+                    matrix[to][from] += *change as i64;
+                }
+            }
+            for (element, change) in instruction.base_count_change.iter() {
+                let to = pairs.len() + elements.binary_search(element).unwrap();
+                matrix[to][from] += *change as i64;
+            }
+        }
+
+        // A pair loses itself and gains the pairs it makes, so with the identity added every
+        // entry ends up at zero or more.
+        PairTransition {
+            pairs,
+            elements,
+            matrix: matrix
+                .into_iter()
+                .map(|row| row.into_iter().map(|n| n as u128).collect())
+                .collect(),
+        }
+    }
+
+    fn get_state(
+        &self,
+        instruction_frequency_map: &InstructionFrequencyMap,
+        base_frequency: &BaseFrequencyMap,
+    ) -> Vec<u128> {
+        self.pairs
+            .iter()
+            .map(|pair| *instruction_frequency_map.get(pair).unwrap_or(&0))
+            .chain(
+                self.elements
+                    .iter()
+                    .map(|element| *base_frequency.get(element).unwrap_or(&0)),
+            )
+            .map(|count| count as u128)
+            .collect()
+    }
+
+    fn count(
+        &self,
+        parse_output: &ParseOutput,
+        steps: u64,
+        modulus: Option<u128>,
+    ) -> Option<Vec<(char, u128)>> {
+        let (_, instruction_frequency_map, base_frequency) = parse_output;
+        let state: Vec<u128> = self
+            .get_state(instruction_frequency_map, base_frequency)
+            .into_iter()
+            .map(|count| modulus.map_or(count, |modulus| count % modulus))
+            .collect();
+        let power = matrix_pow(&self.matrix, steps, modulus)?;
+
+        self.elements
+            .iter()
+            .enumerate()
+            .map(|(i, element)| {
+                let row = &power[self.pairs.len() + i];
+                let count = row
+                    .iter()
+                    .zip(&state)
+                    .try_fold(0, |sum, (a, b)| mul_add(sum, *a, *b, modulus))?;
+                Some((*element, count))
+            })
+            .collect()
+    }
+
+    /// Exact element counts after `steps`, sorted by element. `None` once they outgrow
+    /// `u128`, which happens after about 120 steps.
+    pub fn count_elements(
+        &self,
+        parse_output: &ParseOutput,
+        steps: u64,
+    ) -> Option<Vec<(char, u128)>> {
+        self.count(parse_output, steps, None)
+    }
+
+    /// Element counts after `steps` modulo `modulus`, which works for any number of steps.
+    pub fn count_elements_modulo(
+        &self,
+        parse_output: &ParseOutput,
+        steps: u64,
+        modulus: u64,
+    ) -> Vec<(char, u64)> {
+        assert!(modulus > 0, "modulus must be positive");
+        self.count(parse_output, steps, Some(modulus as u128))
+            .unwrap()
+            .into_iter()
+            .map(|(element, count)| (element, count as u64))
+            .collect()
+    }
+}
+
+/// `sum + a * b`, reduced by `modulus` if there is one and checked for overflow if not.
+/// With a modulus of at most `u64::MAX` the reduced values never overflow.
+fn mul_add(sum: u128, a: u128, b: u128, modulus: Option<u128>) -> Option<u128> {
+    match modulus {
+        Some(modulus) => Some((sum + a * b % modulus) % modulus),
+        None => sum.checked_add(a.checked_mul(b)?),
+    }
+}
+
+fn matrix_mul(a: &Matrix, b: &Matrix, modulus: Option<u128>) -> Option<Matrix> {
+    let size = a.len();
+    let mut product: Matrix = vec![vec![0; size]; size];
+    for i in 0..size {
+        for k in 0..size {
+            if a[i][k] == 0 {
+                continue;
+            }
+            for j in 0..size {
+                product[i][j] = mul_add(product[i][j], a[i][k], b[k][j], modulus)?;
+            }
+        }
+    }
+    Some(product)
+}
+
+fn matrix_pow(matrix: &Matrix, mut exponent: u64, modulus: Option<u128>) -> Option<Matrix> {
+    let size = matrix.len();
+    let mut result: Matrix = (0..size)
+        .map(|i| (0..size).map(|j| (i == j) as u128).collect())
+        .collect();
+    let mut base = matrix.clone();
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = matrix_mul(&result, &base, modulus)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = matrix_mul(&base, &base, modulus)?;
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input::{parse, read_main, read_test};
+
+    #[test]
+    fn exact_counts_test() {
+        let parse_output = parse(&read_test());
+        let transition = PairTransition::new(&parse_output);
+        assert_eq!(
+            transition.count_elements(&parse_output, 10),
+            Some(vec![('B', 1749), ('C', 298), ('H', 161), ('N', 865)])
+        );
+        assert_eq!(
+            transition.count_elements(&parse_output, 0),
+            Some(vec![('B', 1), ('C', 1), ('H', 0), ('N', 2)])
+        );
+        assert!(transition.count_elements(&parse_output, 130).is_none());
+    }
+
+    #[test]
+    fn modulo_counts_test() {
+        let parse_output = parse(&read_main());
+        let transition = PairTransition::new(&parse_output);
+        let modulus = 1_000_000_007;
+
+        let exact = transition.count_elements(&parse_output, 60).unwrap();
+        let reduced = transition.count_elements_modulo(&parse_output, 60, modulus);
+        for ((element, count), (reduced_element, reduced_count)) in exact.iter().zip(&reduced) {
+            assert_eq!(element, reduced_element);
+            assert_eq!((count % modulus as u128) as u64, *reduced_count);
+        }
+
+        // Every pair has a rule, so the polymer of length `n` grows to `(n - 1) * 2^steps + 1`.
+        let steps: u64 = 1_000_000_000_000;
+        let template_length = read_main().lines().next().unwrap().len() as u64;
+        let mut length = template_length - 1;
+        let (mut base, mut exponent) = (2u64, steps);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                length = length * base % modulus;
+            }
+            base = base * base % modulus;
+            exponent >>= 1;
+        }
+        let counts = transition.count_elements_modulo(&parse_output, steps, modulus);
+        let total = counts
+            .iter()
+            .fold(0, |sum, (_, count)| (sum + count) % modulus);
+        assert_eq!(total, (length + 1) % modulus);
+    }
+}
@@ -1,146 +1,88 @@
 #![feature(test)]
-use crate::parse_input::{parse, read_main, ParseOutput};
-use std::cmp::{max, min};
+use crate::parse_input::{parse, read_file, read_main, ParseOutput};
+use crate::render::{get_flight_steps, render_trajectory};
+use crate::solver::{Physics, Target};
 
 pub mod parse_input;
+pub mod render;
+pub mod solver;
 
 type Solution = i32;
 
 fn main() {
-    let parse_output = parse(&read_main());
-    println!("Solution to part 1 is {}", part_1(&parse_output));
-    println!("Solution to part 2 is {}", part_2(&parse_output));
-}
-
-fn part_1(parse_output: &ParseOutput) -> Solution {
-    let ((y1, _), (y2, _)) = parse_output.clone();
-
-    max_y(max_y_v(y1, y2))
-}
-
-fn part_2(parse_output: &ParseOutput) -> Solution {
-    let ((y1, x1), (y2, x2)) = parse_output.clone();
-    let y_range = (y1, y2);
-    let x_range = (x1, x2);
-
-    let max_y_velocity = max_y_v(y1, y2);
-    let min_y_velocity = min_y_v(y1, y2);
-    let min_x_velocity = min_x_v(x1, x2);
-    let max_x_velocity = max_x_v(x1, x2);
-    let mut current_x_v;
-    let mut current_y_v = min_y_velocity;
-    let mut valid_combinations = 0;
-
-    for _ in 0..=max_y_velocity - min_y_velocity {
-        current_x_v = min_x_velocity;
-        for _ in 0..=max_x_velocity - min_x_velocity {
-            let (t0, t1) = get_hit_t_y(current_y_v, y_range);
-            let x_at0 = x_at_t(t0, current_x_v);
-            let x_at1 = x_at_t(t1, current_x_v);
-            if in_range(x_at0, x_range.0, x_range.1) || in_range(x_at1, x_range.0, x_range.1) {
-                valid_combinations += 1;
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("launches") {
+        let file = read_file(args.get(2).map_or("src/17.txt", String::as_str));
+        print_launches(&parse(&file));
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("render") {
+        match parse_render_args(&args[2..]) {
+            Ok((vx, vy, physics)) => print_trajectory(&parse(&read_main()), &physics, vx, vy),
+            Err(err) => {
+                println!("{}", err);
+                println!("usage: render [vx] [vy] [drag] [gravity]");
             }
-
-            current_x_v += 1;
         }
-        current_y_v += 1;
-    }
-
-    valid_combinations
-}
-
-fn get_hit_t_y(v: i32, range: (i32, i32)) -> (i32, i32) {
-    let y0 = range.0;
-    let y1 = range.1;
-    let t0 = get_t_for_when_target_hit(v, y0);
-    let t1 = get_t_for_when_target_hit(v, y1);
-
-    let mut t0_in_range = -1;
-    let mut t1_in_range = -1;
-
-    let lower_bound_t0 = t0.floor() as i32;
-    let upper_bound_t0 = t0.ceil() as i32;
-    let lower_bound_t1 = t1.floor() as i32;
-    let upper_bound_t1 = t1.ceil() as i32;
-
-    if in_range(y_at_t(lower_bound_t0, v), y0, y1) {
-        t0_in_range = lower_bound_t0;
-    }
-    if in_range(y_at_t(upper_bound_t0, v), y0, y1) {
-        t0_in_range = upper_bound_t0;
-    }
-    if in_range(y_at_t(lower_bound_t1, v), y0, y1) {
-        t1_in_range = lower_bound_t1;
+        return;
     }
-    if in_range(y_at_t(upper_bound_t1, v), y0, y1) {
-        t1_in_range = upper_bound_t1;
-    }
-
-    (t0_in_range, t1_in_range)
-}
 
-fn in_range(n: i32, s: i32, e: i32) -> bool {
-    let min = min(s, e);
-    let max = max(s, e);
-    n >= min && n <= max
+    let parse_output = parse(&read_main());
+    println!("Solution to part 1 is {}", part_1(&parse_output));
+    println!("Solution to part 2 is {}", part_2(&parse_output));
 }
 
-fn get_t_for_when_target_hit(v: i32, target: i32) -> f64 {
-    let a = -0.5_f64;
-    let b = v as f64 + 0.5_f64;
-    let c = -target as f64;
-    let sqrt_examination: f64 = (b * b) - (4_f64 * a * c);
-    let sqrt = sqrt_examination.sqrt();
-    if sqrt_examination >= 0_f64 {
-        let t1 = (-b + sqrt) / (2_f64 * a);
-        let t2 = (-b - sqrt) / (2_f64 * a);
-
-        if t1 > 0_f64 {
-            return t1;
-        }
-        return t2;
+/// Launch velocity and physics for `render`, every one of them optional. The velocity
+/// defaults to 0 and drag and gravity to 1.
+fn parse_render_args(args: &[String]) -> Result<(i64, i64, Physics), String> {
+    let arg = |i: usize, default: i64| match args.get(i) {
+        Some(arg) => arg
+            .parse()
+            .map_err(|_| format!("`{}` is not a whole number", arg)),
+        None => Ok(default),
+    };
+    if args.len() > 4 {
+        return Err(format!("expected at most 4 arguments, got {}", args.len()));
     }
-
-    -1_f64
+    let physics = Physics::new(arg(2, 1)?, arg(3, 1)?)?;
+    Ok((arg(0, 0)?, arg(1, 0)?, physics))
 }
 
-fn max_y_v(y1: i32, y2: i32) -> i32 {
-    let max = min(y1, y2) + 1;
-    -max
+/// The flight of a probe launched with `(vx, vy)` and the target as ASCII art.
+fn print_trajectory(parse_output: &ParseOutput, physics: &Physics, vx: i64, vy: i64) {
+    let target = Target::from_parse_output(parse_output);
+    let steps = get_flight_steps(&target, physics, vx, vy);
+    print!("{}", render_trajectory(&target, physics, vx, vy, steps));
 }
 
-fn min_y_v(y1: i32, y2: i32) -> i32 {
-    let max = min(y1, y2);
-    max
-}
-
-fn min_x_v(x1: i32, x2: i32) -> i32 {
-    let max = max(x1, x2);
-    let x = max as f64;
-    let sqrt = ((8_f64 * x) + 1_f64).sqrt();
-    (0.5_f64 * (sqrt - 1_f64)) as i32 - 1
-}
-
-fn max_x_v(x1: i32, x2: i32) -> i32 {
-    max(x1, x2)
-}
-
-fn max_y(v: i32) -> i32 {
-    // t_max is v + 0.5
-    let t_max = v + 1;
-    y_at_t(t_max, v)
+fn part_1(parse_output: &ParseOutput) -> Solution {
+    let launches = Target::from_parse_output(parse_output)
+        .find_launches(&Physics::standard())
+        .unwrap();
+    launches
+        .iter()
+        .map(|launch| launch.get_highest_y(&Physics::standard()))
+        .max()
+        .unwrap() as i32
 }
 
-fn y_at_t(t: i32, v: i32) -> i32 {
-    (v * t) - ((t * t) / 2) + (t / 2)
+fn part_2(parse_output: &ParseOutput) -> Solution {
+    let launches = Target::from_parse_output(parse_output)
+        .find_launches(&Physics::standard())
+        .unwrap();
+    launches.len() as i32
 }
 
-fn x_at_t(t: i32, v: i32) -> i32 {
-    let mut clamped_t = t;
-    if t > v {
-        clamped_t = v;
+/// Every launch velocity that hits the target, one per line.
+fn print_launches(parse_output: &ParseOutput) {
+    match Target::from_parse_output(parse_output).find_launches(&Physics::standard()) {
+        Some(launches) => {
+            for launch in launches {
+                println!("{}", launch);
+            }
+        }
+        None => println!("Infinitely many launches hit the target"),
     }
-    (v * clamped_t) - ((clamped_t * clamped_t) / 2) + (clamped_t / 2)
 }
 
 #[cfg(test)]
@@ -162,6 +104,23 @@ mod tests {
         assert_eq!(part_2(&parse_output), 112);
     }
 
+    #[test]
+    pub fn test_parse_render_args() {
+        let to_strings = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            parse_render_args(&to_strings(&[])),
+            Ok((0, 0, Physics::standard()))
+        );
+        assert_eq!(
+            parse_render_args(&to_strings(&["7", "-2", "3"])),
+            Ok((7, -2, Physics::new(3, 1).unwrap()))
+        );
+        assert!(parse_render_args(&to_strings(&["7", "x"])).is_err());
+        assert!(parse_render_args(&to_strings(&["7", "2", "0", "1"])).is_err());
+        assert!(parse_render_args(&to_strings(&["7", "2", "1", "-1"])).is_err());
+        assert!(parse_render_args(&to_strings(&["7", "2", "1", "1", "1"])).is_err());
+    }
+
     #[bench]
     fn bench_parse(b: &mut Bencher) {
         let file = read_main();
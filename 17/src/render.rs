@@ -0,0 +1,111 @@
+use crate::solver::{Physics, Target};
+
+/// Steps worth drawing for a launch: up to the first step inside the target or, if it
+/// misses, up to the first step below the target on the way down.
+pub fn get_flight_steps(target: &Target, physics: &Physics, vx: i64, vy: i64) -> i64 {
+    let mut t = 1;
+    loop {
+        let (x, y) = physics.get_position(vx, vy, t);
+        let falling = vy - physics.get_gravity() * (t - 1) <= 0;
+        if target.contains(x, y) || (falling && y < target.y_min) {
+            return t;
+        }
+        t += 1;
+    }
+}
+
+/// Draws the trajectory like the puzzle does, the top row being the highest point. `S` is
+/// the launch point, `#` the probe after each of the `steps` steps and `T` the target.
+pub fn render_trajectory(
+    target: &Target,
+    physics: &Physics,
+    vx: i64,
+    vy: i64,
+    steps: i64,
+) -> String {
+    let positions: Vec<(i64, i64)> = (1..=steps)
+        .map(|t| physics.get_position(vx, vy, t))
+        .collect();
+    let x_min = positions
+        .iter()
+        .map(|(x, _)| *x)
+        .fold(target.x_min.min(0), i64::min);
+    let x_max = positions
+        .iter()
+        .map(|(x, _)| *x)
+        .fold(target.x_max.max(0), i64::max);
+    let y_min = positions
+        .iter()
+        .map(|(_, y)| *y)
+        .fold(target.y_min.min(0), i64::min);
+    let y_max = positions
+        .iter()
+        .map(|(_, y)| *y)
+        .fold(target.y_max.max(0), i64::max);
+
+    let mut rendering = String::new();
+    for y in (y_min..=y_max).rev() {
+        for x in x_min..=x_max {
+            rendering.push(if (x, y) == (0, 0) {
+                'S'
+            } else if positions.contains(&(x, y)) {
+                '#'
+            } else if target.contains(x, y) {
+                'T'
+            } else {
+                '.'
+            });
+        }
+        rendering.push('\n');
+    }
+    rendering
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input::{parse, read_test};
+
+    #[test]
+    fn example_trajectory_test() {
+        let target = Target::from_parse_output(&parse(&read_test()));
+        let physics = Physics::standard();
+        let steps = get_flight_steps(&target, &physics, 7, 2);
+        assert_eq!(steps, 7);
+        assert_eq!(
+            render_trajectory(&target, &physics, 7, 2, steps),
+            ".............#....#............\n\
+             .......#..............#........\n\
+             ...............................\n\
+             S........................#.....\n\
+             ...............................\n\
+             ...............................\n\
+             ...........................#...\n\
+             ...............................\n\
+             ....................TTTTTTTTTTT\n\
+             ....................TTTTTTTTTTT\n\
+             ....................TTTTTTTT#TT\n\
+             ....................TTTTTTTTTTT\n\
+             ....................TTTTTTTTTTT\n\
+             ....................TTTTTTTTTTT\n"
+        );
+    }
+
+    #[test]
+    fn other_physics_test() {
+        // Shot left with drag 2 and gravity 2: sideways 3, 1, up 1, then down 1 and 3.
+        let target = Target::new(-4, -3, -4, -3);
+        let physics = Physics::new(2, 2).unwrap();
+        let steps = get_flight_steps(&target, &physics, -3, 1);
+        assert_eq!(steps, 3);
+        assert_eq!(
+            render_trajectory(&target, &physics, -3, 1, steps),
+            ".#...\n\
+             #...S\n\
+             .....\n\
+             .....\n\
+             #T...\n\
+             TT...\n"
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use crate::parse_input::ParseOutput;
+use std::fmt;
+
+/// First and last step a probe is between the sides of the target, no last step if it
+/// comes to rest there. `None` if it never is.
+type XSteps = Option<(i64, Option<i64>)>;
+
+/// The target area, bounds included. It can be anywhere around the launch point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Target {
+    pub x_min: i64,
+    pub x_max: i64,
+    pub y_min: i64,
+    pub y_max: i64,
+}
+
+/// How fast the probe slows down sideways and falls. Drag brings the horizontal speed
+/// closer to 0 by `drag` each step without going past it, gravity takes `gravity` off the
+/// vertical speed each step. Both have to be positive so every probe comes to rest and
+/// falls, which is why they can only be set through `new`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Physics {
+    drag: i64,
+    gravity: i64,
+}
+
+impl Physics {
+    pub fn new(drag: i64, gravity: i64) -> Result<Self, String> {
+        match drag > 0 && gravity > 0 {
+            true => Ok(Physics { drag, gravity }),
+            false => Err(format!(
+                "drag and gravity must be positive, got {} and {}",
+                drag, gravity
+            )),
+        }
+    }
+
+    /// Drag and gravity of one, as in the puzzle.
+    pub fn standard() -> Self {
+        Physics {
+            drag: 1,
+            gravity: 1,
+        }
+    }
+
+    pub fn get_drag(&self) -> i64 {
+        self.drag
+    }
+
+    pub fn get_gravity(&self) -> i64 {
+        self.gravity
+    }
+
+    /// Steps a probe launched with `vx` moves before it comes to rest.
+    pub fn get_moving_steps(&self, vx: i64) -> i64 {
+        (vx.abs() + self.drag - 1) / self.drag
+    }
+
+    /// Position after `t` steps.
+    pub fn get_position(&self, vx: i64, vy: i64, t: i64) -> (i64, i64) {
+        (x_at_t(t, vx, self.drag), y_at_t(t, vy, self.gravity))
+    }
+}
+
+/// A launch velocity that ends up in the target, with the ranges of steps, bounds
+/// included, during which the probe is inside. A probe launched upwards through a target
+/// above the launch point is inside once on the way up and once on the way down.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Launch {
+    pub vx: i64,
+    pub vy: i64,
+    pub steps: Vec<(i64, i64)>,
+}
+
+impl Target {
+    pub fn new(x1: i64, x2: i64, y1: i64, y2: i64) -> Self {
+        Target {
+            x_min: x1.min(x2),
+            x_max: x1.max(x2),
+            y_min: y1.min(y2),
+            y_max: y1.max(y2),
+        }
+    }
+
+    pub fn from_parse_output(parse_output: &ParseOutput) -> Self {
+        let ((y1, x1), (y2, x2)) = *parse_output;
+        Target::new(x1 as i64, x2 as i64, y1 as i64, y2 as i64)
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        (self.x_min..=self.x_max).contains(&x) && (self.y_min..=self.y_max).contains(&y)
+    }
+
+    /// Steps during which a probe launched with `vx` is between the sides of the target,
+    /// `None` as the end if it comes to rest there.
+    fn get_x_steps(&self, vx: i64, physics: &Physics) -> XSteps {
+        let in_range = |x: i64| (self.x_min..=self.x_max).contains(&x);
+        let moving_steps = physics.get_moving_steps(vx);
+        let mut steps: Option<(i64, i64)> = None;
+        for t in 1..=moving_steps {
+            if in_range(x_at_t(t, vx, physics.get_drag())) {
+                steps = Some((steps.map_or(t, |(first, _)| first), t));
+            }
+        }
+        match in_range(x_at_t(moving_steps, vx, physics.get_drag())) {
+            true => Some((steps.map_or(1, |(first, _)| first), None)),
+            false => steps.map(|(first, last)| (first, Some(last))),
+        }
+    }
+
+    /// Steps during which a probe launched with `vy` is between the top and bottom of the
+    /// target. Once it falls below the bottom it never comes back.
+    fn get_y_steps(&self, vy: i64, physics: &Physics) -> Vec<(i64, i64)> {
+        let mut steps: Vec<(i64, i64)> = Vec::new();
+        let mut t = 1;
+        loop {
+            let y = y_at_t(t, vy, physics.get_gravity());
+            if vy - physics.get_gravity() * (t - 1) <= 0 && y < self.y_min {
+                return steps;
+            }
+            if (self.y_min..=self.y_max).contains(&y) {
+                match steps.last_mut() {
+                    Some((_, last)) if *last == t - 1 => *last = t,
+                    _ => steps.push((t, t)),
+                }
+            }
+            t += 1;
+        }
+    }
+
+    /// Every launch that is inside the target after some step, ordered by `vy` then `vx`.
+    /// `None` if there are infinitely many, which happens when the target spans the
+    /// height of the launch point and a probe can come to rest between its sides: any
+    /// probe shot upwards with a multiple of gravity returns to that height.
+    pub fn find_launches(&self, physics: &Physics) -> Option<Vec<Launch>> {
+        let reach = self.x_min.abs().max(self.x_max.abs());
+        let vx_range = self.x_min.min(0)..=self.x_max.max(0);
+        let x_steps: Vec<(i64, XSteps)> = vx_range
+            .map(|vx| (vx, self.get_x_steps(vx, physics)))
+            .collect();
+
+        // A probe at a height `h` above the launch point after `t` steps has `2 * h` as a
+        // multiple of `t`, so shot up faster than `y_max * (gravity + 1)` it passes the
+        // target without stopping in it. Once it is below the launch point it is lower
+        // than `-vy / gravity`. That leaves coming back to the launch height exactly,
+        // after `2 * vy / gravity + 1` steps, by when probes shot up faster than
+        // `gravity * reach` have come to rest.
+        let g = physics.get_gravity();
+        let spans_launch_height = self.y_min <= 0 && 0 <= self.y_max;
+        if spans_launch_height && x_steps.iter().any(|(_, s)| matches!(s, Some((_, None)))) {
+            return None;
+        }
+        let vy_max = (-self.y_min * g)
+            .max(self.y_max * (g + 1))
+            .max(if spans_launch_height { reach * g } else { 0 });
+
+        let mut launches = Vec::new();
+        for vy in self.y_min.min(0)..=vy_max {
+            let y_steps = self.get_y_steps(vy, physics);
+            for (vx, x_steps) in x_steps.iter() {
+                let (x_first, x_last) = match x_steps {
+                    Some(steps) => steps,
+                    None => continue,
+                };
+                let steps: Vec<(i64, i64)> = y_steps
+                    .iter()
+                    .map(|(y_first, y_last)| {
+                        (
+                            *y_first.max(x_first),
+                            *y_last.min(&x_last.unwrap_or(i64::MAX)),
+                        )
+                    })
+                    .filter(|(first, last)| first <= last)
+                    .collect();
+                if !steps.is_empty() {
+                    launches.push(Launch { vx: *vx, vy, steps });
+                }
+            }
+        }
+        Some(launches)
+    }
+}
+
+impl Launch {
+    /// The highest point of the trajectory, which is the launch point when shot downwards.
+    pub fn get_highest_y(&self, physics: &Physics) -> i64 {
+        let rising_steps = (self.vy.max(0) + physics.get_gravity() - 1) / physics.get_gravity();
+        y_at_t(rising_steps, self.vy, physics.get_gravity())
+    }
+}
+
+impl fmt::Display for Launch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps: Vec<String> = self
+            .steps
+            .iter()
+            .map(|(first, last)| match first == last {
+                true => format!("{}", first),
+                false => format!("{}..={}", first, last),
+            })
+            .collect();
+        write!(f, "{},{} at steps {}", self.vx, self.vy, steps.join(", "))
+    }
+}
+
+/// Height after `t` steps, gravity slowing the probe by `gravity` each step.
+pub fn y_at_t(t: i64, v: i64, gravity: i64) -> i64 {
+    v * t - gravity * t * (t - 1) / 2
+}
+
+/// Distance after `t` steps, drag slowing the probe by `drag` each step until it stops.
+pub fn x_at_t(t: i64, v: i64, drag: i64) -> i64 {
+    let t = t.min((v.abs() + drag - 1) / drag);
+    v.signum() * (v.abs() * t - drag * t * (t - 1) / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input::{parse, read_test};
+
+    /// Launches found by flying every probe in a box of velocities for a fixed time.
+    fn simulate(target: &Target, physics: &Physics, v_range: i64, t_max: i64) -> Vec<Launch> {
+        let mut launches = Vec::new();
+        for vy in -v_range..=v_range {
+            for vx in -v_range..=v_range {
+                let mut steps: Vec<(i64, i64)> = Vec::new();
+                let (mut x, mut y, mut dx, mut dy) = (0, 0, vx, vy);
+                for t in 1..=t_max {
+                    x += dx;
+                    y += dy;
+                    dx -= dx.signum() * dx.abs().min(physics.get_drag());
+                    dy -= physics.get_gravity();
+                    if target.contains(x, y) {
+                        match steps.last_mut() {
+                            Some((_, last)) if *last == t - 1 => *last = t,
+                            _ => steps.push((t, t)),
+                        }
+                    }
+                }
+                if !steps.is_empty() {
+                    launches.push(Launch { vx, vy, steps });
+                }
+            }
+        }
+        launches
+    }
+
+    #[test]
+    fn example_launches_test() {
+        let target = Target::from_parse_output(&parse(&read_test()));
+        let physics = Physics::standard();
+        let launches = target.find_launches(&physics).unwrap();
+        assert_eq!(launches.len(), 112);
+
+        let highest = launches.iter().map(|l| l.get_highest_y(&physics)).max();
+        assert_eq!(highest, Some(45));
+
+        let launch = launches.iter().find(|l| (l.vx, l.vy) == (6, 9)).unwrap();
+        assert_eq!(launch.to_string(), "6,9 at steps 20");
+    }
+
+    #[test]
+    fn any_quadrant_test() {
+        for physics in [
+            Physics::standard(),
+            Physics::new(2, 1).unwrap(),
+            Physics::new(1, 3).unwrap(),
+        ] {
+            for target in [
+                Target::new(5, 10, 3, 8),
+                Target::new(-12, -7, -9, -4),
+                Target::new(-3, 4, -10, -5),
+                Target::new(-2, 2, 2, 6),
+                Target::new(7, 8, -3, 3),
+            ] {
+                let launches = target.find_launches(&physics).unwrap();
+                assert!(!launches.is_empty());
+                // Probes that end with their steps still running are cut short by `simulate`.
+                let simulated: Vec<Launch> = simulate(&target, &physics, 40, 2000)
+                    .into_iter()
+                    .filter(|l| l.steps.last().unwrap().1 < 2000)
+                    .collect();
+                assert_eq!(
+                    launches
+                        .into_iter()
+                        .filter(|l| l.steps.last().unwrap().1 < 2000)
+                        .collect::<Vec<_>>(),
+                    simulated,
+                    "{:?} {:?}",
+                    target,
+                    physics
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn passes_twice_test() {
+        let physics = Physics::standard();
+        let launches = Target::new(-2, 2, 2, 6).find_launches(&physics).unwrap();
+        let launch = launches.iter().find(|l| (l.vx, l.vy) == (0, 4)).unwrap();
+        assert_eq!(launch.steps, vec![(1, 1), (8, 8)]);
+        assert_eq!(launch.get_highest_y(&physics), 10);
+        assert_eq!(launch.get_highest_y(&Physics::new(1, 3).unwrap()), 5);
+    }
+
+    #[test]
+    fn physics_test() {
+        let physics = Physics::new(2, 3).unwrap();
+        // Sideways 5, 3, 1 and then at rest, up 4, 1, then down 2, 5.
+        let positions: Vec<(i64, i64)> = (0..=5).map(|t| physics.get_position(5, 4, t)).collect();
+        assert_eq!(
+            positions,
+            vec![(0, 0), (5, 4), (8, 5), (9, 3), (9, -2), (9, -10)]
+        );
+        assert_eq!(physics.get_position(-5, 0, 3), (-9, -9));
+        assert!(Physics::new(0, 1).is_err());
+        assert!(Physics::new(1, -1).is_err());
+    }
+
+    #[test]
+    fn infinitely_many_test() {
+        let physics = Physics::standard();
+        // A probe launched with vx = 3 stops at x = 6, and every probe shot up returns to 0.
+        assert_eq!(Target::new(5, 7, -2, 2).find_launches(&physics), None);
+        assert_eq!(Target::new(-1, 1, -1, 0).find_launches(&physics), None);
+        assert!(Target::new(7, 8, -2, 2).find_launches(&physics).is_some());
+        // A probe launched with vx = 4 stops at x = 10, with more drag nothing stops there.
+        assert_eq!(Target::new(10, 11, -2, 2).find_launches(&physics), None);
+        assert!(Target::new(10, 11, -2, 2)
+            .find_launches(&Physics::new(3, 2).unwrap())
+            .is_some());
+    }
+}
@@ -1,14 +1,12 @@
+use std::array::from_fn;
+use std::ops::Mul;
+
 pub type Point = [i32; 3];
-pub type Transformation = (usize, usize, usize, i32, i32, i32);
 
 pub fn minus(p1: Point, p2: Point) -> Point {
     return [p2[0] - p1[0], p2[1] - p1[1], p2[2] - p1[2]];
 }
 
-pub fn rotate_point(p: Point, t1: Transformation) -> Point {
-    return [p[t1.0] * t1.3, p[t1.1] * t1.4, p[t1.2] * t1.5];
-}
-
 pub fn translate_point(p1: Point, t: Point) -> Point {
     return [p1[0] + t[0], p1[1] + t[1], p1[2] + t[2]];
 }
@@ -17,11 +15,156 @@ pub fn point_distance(p1: Point, p2: Point) -> i32 {
     (p2[0] - p1[0]).pow(2) + (p2[1] - p1[1]).pow(2) + (p2[2] - p1[2]).pow(2)
 }
 
+/// An integer 3x3 matrix. Scanners are only ever turned in 90 degree steps, so every
+/// rotation we deal with is a signed permutation matrix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    m: [[i32; 3]; 3],
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        m: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    };
+
+    /// Coordinate `i` of a rotated point is `signs[i] * p[axes[i]]`.
+    pub fn from_axes(axes: [usize; 3], signs: [i32; 3]) -> Rotation {
+        let mut m = [[0; 3]; 3];
+        for row in 0..3 {
+            m[row][axes[row]] = signs[row];
+        }
+        Rotation { m }
+    }
+
+    /// All 24 orientations a scanner can be in. Signed permutations with a negative
+    /// determinant would mirror the scanner, so those are left out.
+    pub fn all() -> [Rotation; 24] {
+        let permutations = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let mut rotations = [Rotation::IDENTITY; 24];
+        let mut i = 0;
+        for axes in permutations {
+            for signs in 0..8 {
+                let signs = [
+                    if signs & 1 == 0 { 1 } else { -1 },
+                    if signs & 2 == 0 { 1 } else { -1 },
+                    if signs & 4 == 0 { 1 } else { -1 },
+                ];
+                let rotation = Rotation::from_axes(axes, signs);
+                if rotation.is_proper() {
+                    rotations[i] = rotation;
+                    i += 1;
+                }
+            }
+        }
+        rotations
+    }
+
+    pub fn matrix(&self) -> [[i32; 3]; 3] {
+        self.m
+    }
+
+    pub fn apply(&self, p: Point) -> Point {
+        let m = &self.m;
+        [
+            m[0][0] * p[0] + m[0][1] * p[1] + m[0][2] * p[2],
+            m[1][0] * p[0] + m[1][1] * p[1] + m[1][2] * p[2],
+            m[2][0] * p[0] + m[2][1] * p[1] + m[2][2] * p[2],
+        ]
+    }
+
+    pub fn determinant(&self) -> i32 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// A rotation and not a reflection.
+    pub fn is_proper(&self) -> bool {
+        self.determinant() == 1 && *self * self.transpose() == Rotation::IDENTITY
+    }
+
+    /// For rotation matrices the transpose is the inverse.
+    pub fn inverse(&self) -> Rotation {
+        self.transpose()
+    }
+
+    fn transpose(&self) -> Rotation {
+        Rotation {
+            m: from_fn(|row| from_fn(|column| self.m[column][row])),
+        }
+    }
+}
+
+/// `a * b` rotates by `b` first and then by `a`.
+impl Mul for Rotation {
+    type Output = Rotation;
+
+    fn mul(self, other: Rotation) -> Rotation {
+        Rotation {
+            m: from_fn(|row| {
+                from_fn(|column| (0..3).map(|k| self.m[row][k] * other.m[k][column]).sum())
+            }),
+        }
+    }
+}
+
+/// Rotates a point and then moves it, mapping points from one scanner's coordinate
+/// system into another one's.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RigidTransform {
+    pub rotation: Rotation,
+    pub translation: Point,
+}
+
+impl RigidTransform {
+    pub const IDENTITY: RigidTransform = RigidTransform {
+        rotation: Rotation::IDENTITY,
+        translation: [0, 0, 0],
+    };
+
+    pub fn new(rotation: Rotation, translation: Point) -> RigidTransform {
+        RigidTransform {
+            rotation,
+            translation,
+        }
+    }
+
+    pub fn apply(&self, p: Point) -> Point {
+        translate_point(self.rotation.apply(p), self.translation)
+    }
+
+    /// The transform that applies `other` first and then `self`.
+    pub fn compose(&self, other: &RigidTransform) -> RigidTransform {
+        RigidTransform {
+            rotation: self.rotation * other.rotation,
+            translation: self.apply(other.translation),
+        }
+    }
+
+    pub fn inverse(&self) -> RigidTransform {
+        let rotation = self.rotation.inverse();
+        let [x, y, z] = rotation.apply(self.translation);
+        RigidTransform {
+            rotation,
+            translation: [-x, -y, -z],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     extern crate test;
 
-    use crate::algebruh::{point_distance, rotate_point};
+    use crate::algebruh::{point_distance, RigidTransform, Rotation};
+    use std::collections::HashSet;
 
     #[test]
     pub fn test_distance() {
@@ -34,30 +177,77 @@ mod tests {
     #[test]
     pub fn test_transformations() {
         assert_eq!(
-            rotate_point([686, 422, 578], (0, 1, 2, 1, 1, 1)),
+            Rotation::from_axes([0, 1, 2], [1, 1, 1]).apply([686, 422, 578]),
             [686, 422, 578]
         );
         assert_eq!(
-            rotate_point([686, 422, 578], (1, 0, 2, 1, 1, 1)),
+            Rotation::from_axes([1, 0, 2], [1, 1, 1]).apply([686, 422, 578]),
             [422, 686, 578]
         );
         assert_eq!(
-            rotate_point([686, 422, 578], (0, 2, 1, 1, 1, 1)),
+            Rotation::from_axes([0, 2, 1], [1, 1, 1]).apply([686, 422, 578]),
             [686, 578, 422]
         );
         assert_eq!(
-            rotate_point([686, 422, 578], (0, 1, 2, -1, -1, -1)),
+            Rotation::from_axes([0, 1, 2], [-1, -1, -1]).apply([686, 422, 578]),
             [-686, -422, -578]
         );
 
         assert_eq!(
-            rotate_point([686, 422, 578], (0, 2, 1, -1, -1, -1)),
+            Rotation::from_axes([0, 2, 1], [-1, -1, -1]).apply([686, 422, 578]),
             [-686, -578, -422]
         );
 
         assert_eq!(
-            rotate_point([686, 422, 578], (0, 2, 1, -1, 1, -1)),
+            Rotation::from_axes([0, 2, 1], [-1, 1, -1]).apply([686, 422, 578]),
             [-686, 578, -422]
         );
     }
+
+    #[test]
+    pub fn test_all_rotations() {
+        let rotations = Rotation::all();
+        let unique: HashSet<Rotation> = HashSet::from_iter(rotations);
+        assert_eq!(unique.len(), 24);
+
+        for r in &rotations {
+            assert_eq!(r.determinant(), 1);
+            assert!(r.is_proper());
+            assert_eq!(*r * r.inverse(), Rotation::IDENTITY);
+            for o in &rotations {
+                assert!(unique.contains(&(*r * *o)));
+            }
+        }
+
+        let mirror_x = Rotation::from_axes([0, 1, 2], [-1, 1, 1]);
+        let swap_xy = Rotation::from_axes([1, 0, 2], [1, 1, 1]);
+        assert!(!mirror_x.is_proper());
+        assert!(!swap_xy.is_proper());
+        assert!(!unique.contains(&mirror_x));
+        assert!(!unique.contains(&swap_xy));
+    }
+
+    #[test]
+    pub fn test_rotation_composition() {
+        let a = Rotation::from_axes([1, 0, 2], [-1, 1, 1]);
+        let b = Rotation::from_axes([0, 2, 1], [1, -1, 1]);
+        let p = [686, 422, 578];
+        assert_eq!((a * b).apply(p), a.apply(b.apply(p)));
+        assert_ne!(a * b, b * a);
+    }
+
+    #[test]
+    pub fn test_rigid_transform() {
+        let a = RigidTransform::new(Rotation::from_axes([1, 0, 2], [-1, 1, 1]), [68, -1246, -43]);
+        let b = RigidTransform::new(
+            Rotation::from_axes([2, 1, 0], [1, -1, 1]),
+            [1105, -1205, 1229],
+        );
+        let p = [686, 422, 578];
+
+        assert_eq!(a.compose(&b).apply(p), a.apply(b.apply(p)));
+        assert_eq!(a.inverse().apply(a.apply(p)), p);
+        assert_eq!(a.compose(&a.inverse()), RigidTransform::IDENTITY);
+        assert_eq!(a.compose(&b).inverse(), b.inverse().compose(&a.inverse()));
+    }
 }
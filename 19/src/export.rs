@@ -0,0 +1,135 @@
+use crate::algebruh::Point;
+use crate::{PathMap, ScannerMap};
+use std::cmp::{max, min};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+fn point_to_json(p: &Point) -> String {
+    format!("[{}, {}, {}]", p[0], p[1], p[2])
+}
+
+/// All beacons and every scanner's position and orientation relative to scanner 0.
+pub fn to_json(beacons: &[Point], scanner_map: &ScannerMap) -> String {
+    let beacons: Vec<String> = beacons
+        .iter()
+        .map(|b| format!("    {}", point_to_json(b)))
+        .collect();
+
+    let scanners: Vec<String> = scanner_map
+        .iter()
+        .enumerate()
+        .map(|(id, transform)| {
+            let orientation: Vec<String> = transform
+                .rotation
+                .matrix()
+                .iter()
+                .map(point_to_json)
+                .collect();
+            format!(
+                "    {{\"id\": {}, \"position\": {}, \"orientation\": [{}]}}",
+                id,
+                point_to_json(&transform.translation),
+                orientation.join(", ")
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\n  \"beacons\": [\n{}\n  ],\n  \"scanners\": [\n{}\n  ]\n}}\n",
+        beacons.join(",\n"),
+        scanners.join(",\n")
+    )
+}
+
+/// An ASCII PLY point cloud, beacons in white and scanners in red.
+pub fn to_ply(beacons: &[Point], scanner_map: &ScannerMap) -> String {
+    let mut ply = format!(
+        "ply\nformat ascii 1.0\nelement vertex {}\nproperty int x\nproperty int y\nproperty int z\nproperty uchar red\nproperty uchar green\nproperty uchar blue\nend_header\n",
+        beacons.len() + scanner_map.len()
+    );
+    for b in beacons {
+        ply.push_str(&format!("{} {} {} 255 255 255\n", b[0], b[1], b[2]));
+    }
+    for transform in scanner_map {
+        let s = transform.translation;
+        ply.push_str(&format!("{} {} {} 255 0 0\n", s[0], s[1], s[2]));
+    }
+    ply
+}
+
+/// The scanner overlap graph, one undirected edge per pair of overlapping scanners.
+pub fn to_dot(path_map: &PathMap) -> String {
+    let edges: BTreeSet<(usize, usize)> = path_map
+        .iter()
+        .flat_map(|(s, destinations)| destinations.iter().map(|d| (min(*s, *d), max(*s, *d))))
+        .collect();
+
+    let mut dot = String::from("graph scanners {\n");
+    for (s, d) in edges {
+        dot.push_str(&format!("    {} -- {};\n", s, d));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+pub fn write_all(
+    directory: &Path,
+    beacons: &[Point],
+    scanner_map: &ScannerMap,
+    path_map: &PathMap,
+) -> std::io::Result<()> {
+    std::fs::create_dir_all(directory)?;
+    std::fs::write(
+        directory.join("beacons.json"),
+        to_json(beacons, scanner_map),
+    )?;
+    std::fs::write(directory.join("beacons.ply"), to_ply(beacons, scanner_map))?;
+    std::fs::write(directory.join("scanners.dot"), to_dot(path_map))
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use crate::algebruh::{RigidTransform, Rotation};
+    use crate::export::{to_dot, to_json, to_ply};
+    use std::collections::HashMap;
+
+    #[test]
+    pub fn test_to_json() {
+        let scanner_map = vec![
+            RigidTransform::IDENTITY,
+            RigidTransform::new(Rotation::from_axes([1, 0, 2], [-1, 1, 1]), [68, -1246, -43]),
+        ];
+        assert_eq!(
+            to_json(&vec![[1, 2, 3], [-4, 5, -6]], &scanner_map),
+            "{
+  \"beacons\": [
+    [1, 2, 3],
+    [-4, 5, -6]
+  ],
+  \"scanners\": [
+    {\"id\": 0, \"position\": [0, 0, 0], \"orientation\": [[1, 0, 0], [0, 1, 0], [0, 0, 1]]},
+    {\"id\": 1, \"position\": [68, -1246, -43], \"orientation\": [[0, -1, 0], [1, 0, 0], [0, 0, 1]]}
+  ]
+}
+"
+        );
+    }
+
+    #[test]
+    pub fn test_to_ply() {
+        let ply = to_ply(&vec![[1, 2, 3]], &vec![RigidTransform::IDENTITY]);
+        assert!(ply.starts_with("ply\nformat ascii 1.0\nelement vertex 2\n"));
+        assert!(ply.ends_with("end_header\n1 2 3 255 255 255\n0 0 0 255 0 0\n"));
+    }
+
+    #[test]
+    pub fn test_to_dot() {
+        let path_map = HashMap::from([(0, vec![1]), (1, vec![0, 3]), (3, vec![1]), (4, vec![1])]);
+        assert_eq!(
+            to_dot(&path_map),
+            "graph scanners {\n    0 -- 1;\n    1 -- 3;\n    1 -- 4;\n}\n"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use crate::algebruh::{point_distance, Point};
+use std::cmp::min;
+use std::collections::HashMap;
+
+/// Two scanners have to share this many beacons to be considered overlapping.
+pub const MIN_OVERLAP: usize = 12;
+
+/// 12 shared beacons produce at least 12 * 11 / 2 shared pairwise distances.
+pub const MIN_SHARED_DISTANCES: usize = MIN_OVERLAP * (MIN_OVERLAP - 1) / 2;
+
+pub type BeaconPair = (usize, usize);
+
+/// Squared distances between every pair of beacons of one scanner. Those don't change
+/// under rotation or translation, so they can be compared between scanners before
+/// anything about their orientation is known.
+pub struct Fingerprint {
+    pairs: HashMap<i32, Vec<BeaconPair>>,
+}
+
+impl Fingerprint {
+    pub fn new(points: &[Point]) -> Fingerprint {
+        let mut pairs: HashMap<i32, Vec<BeaconPair>> = HashMap::new();
+        for a in 0..points.len() {
+            for b in a + 1..points.len() {
+                pairs
+                    .entry(point_distance(points[a], points[b]))
+                    .or_default()
+                    .push((a, b));
+            }
+        }
+
+        Fingerprint { pairs }
+    }
+
+    pub fn shared_distances(&self, other: &Fingerprint) -> usize {
+        self.pairs
+            .iter()
+            .filter_map(|(d, pairs)| other.pairs.get(d).map(|o| min(pairs.len(), o.len())))
+            .sum()
+    }
+
+    pub fn might_overlap(&self, other: &Fingerprint) -> bool {
+        self.shared_distances(other) >= MIN_SHARED_DISTANCES
+    }
+
+    /// Beacon pairs that have a distance which occurs exactly once in both scanners.
+    /// Those most likely are the same two beacons seen from both sides, so they can be
+    /// used to solve for the rotation without trying every base point. Can be empty even
+    /// for overlapping scanners, so callers need a fallback.
+    pub fn anchors(&self, other: &Fingerprint) -> Vec<(BeaconPair, BeaconPair)> {
+        self.pairs
+            .iter()
+            .filter(|(_, pairs)| pairs.len() == 1)
+            .filter_map(|(d, pairs)| match other.pairs.get(d) {
+                Some(o) if o.len() == 1 => Some((pairs[0], o[0])),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use crate::fingerprint::{Fingerprint, MIN_SHARED_DISTANCES};
+    use crate::parse_input::{parse, read_test};
+
+    #[test]
+    pub fn test_shared_distances() {
+        let (scanners, _) = parse(&read_test());
+        let fingerprints: Vec<Fingerprint> = scanners.iter().map(|s| Fingerprint::new(s)).collect();
+
+        assert!(fingerprints[0].shared_distances(&fingerprints[1]) >= MIN_SHARED_DISTANCES);
+        assert!(fingerprints[0].might_overlap(&fingerprints[1]));
+        assert!(!fingerprints[0].might_overlap(&fingerprints[2]));
+        assert_eq!(
+            fingerprints[0].shared_distances(&fingerprints[1]),
+            fingerprints[1].shared_distances(&fingerprints[0])
+        );
+    }
+
+    #[test]
+    pub fn test_anchors() {
+        let (scanners, _) = parse(&read_test());
+        let a = Fingerprint::new(&scanners[0]);
+        let b = Fingerprint::new(&scanners[1]);
+
+        let anchors = a.anchors(&b);
+        assert!(!anchors.is_empty());
+        for ((a1, a2), (b1, b2)) in anchors {
+            assert_ne!(a1, a2);
+            assert_ne!(b1, b2);
+        }
+    }
+}
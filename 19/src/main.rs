@@ -1,92 +1,57 @@
 #![feature(test)]
 #![feature(int_abs_diff)]
 
-use crate::algebruh::{
-    minus, point_distance, rotate_point, translate_point, Point, Transformation,
-};
+use crate::algebruh::{minus, translate_point, Point, RigidTransform};
+use crate::fingerprint::{BeaconPair, Fingerprint, MIN_OVERLAP};
 use crate::parse_input::{parse, read_main, ParseOutput, Transformations};
 use std::cmp::max;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 
 pub mod algebruh;
+pub mod export;
+pub mod fingerprint;
 pub mod parse_input;
 
 type Solution = u32;
 
-type ScannerRelationships = HashMap<(usize, usize), (Transformation, Point)>;
+/// `(b, o)` maps the points of scanner `o` into the coordinate system of scanner `b`.
+type ScannerRelationships = HashMap<(usize, usize), RigidTransform>;
+
+type PathMap = HashMap<usize, Vec<usize>>;
+
+/// For every scanner the transform into the coordinate system of scanner 0.
+type ScannerMap = Vec<RigidTransform>;
 
 fn main() {
     let parse_output = parse(&read_main());
     println!("Solution to part 1 is {}", part_1(&parse_output));
     println!("Solution to part 2 is {}", part_2(&parse_output));
+
+    // `cargo run -- <directory>` additionally writes the assembled map for inspection.
+    if let Some(directory) = std::env::args().nth(1) {
+        export_map(&parse_output, Path::new(&directory)).unwrap();
+        println!("Wrote beacon map to {}", directory);
+    }
 }
 
 fn part_1(parse_output: &ParseOutput) -> Solution {
     let (scanner, _possible_rotations) = parse_output;
 
-    let scanner_relationships = get_scanner_relationships(parse_output);
+    let scanner_map = get_scanner_map(scanner.len(), &get_scanner_relationships(parse_output));
 
-    let mut all_beacons_set: HashSet<Point> = HashSet::new();
-
-    let mut path_map: HashMap<usize, Vec<usize>> = HashMap::new();
-    for (r, _) in &scanner_relationships {
-        path_map.entry(r.0).or_insert_with(|| Vec::new()).push(r.1);
-        path_map.entry(r.1).or_insert_with(|| Vec::new()).push(r.0);
-    }
-
-    fill_beacon_set(
-        0,
-        scanner,
-        &path_map,
-        VecDeque::new(),
-        &mut all_beacons_set,
-        &scanner_relationships,
-        &mut HashSet::<(usize, usize)>::new(),
-    );
-
-    all_beacons_set.len() as u32
+    get_beacons(scanner, &scanner_map).len() as u32
 }
 
 fn part_2(parse_output: &ParseOutput) -> Solution {
     let (scanner, _possible_rotations) = parse_output;
 
-    let scanner_relationships = get_scanner_relationships(parse_output);
-
-    let mut path_map: HashMap<usize, Vec<usize>> = HashMap::new();
-    for (r, _) in &scanner_relationships {
-        path_map.entry(r.0).or_insert_with(|| Vec::new()).push(r.1);
-        path_map.entry(r.1).or_insert_with(|| Vec::new()).push(r.0);
-    }
-
-    let mut scanner_positions: Vec<Vec<Point>> = vec![Vec::new(); scanner.len()];
-    scanner_positions[0].push([0, 0, 0]);
-
-    for i in 1..scanner.len() {
-        for d in path_map.get(&i).unwrap() {
-            scanner_positions[i].push(scanner_relationships.get(&(i, *d)).unwrap().1);
-        }
-    }
-
-    println!("{:?}", scanner_positions);
-
-    let mut all_scanners_set: HashSet<Point> = HashSet::new();
-
-    fill_beacon_set(
-        0,
-        &scanner_positions,
-        &path_map,
-        VecDeque::new(),
-        &mut all_scanners_set,
-        &scanner_relationships,
-        &mut HashSet::<(usize, usize)>::new(),
-    );
+    let scanner_map = get_scanner_map(scanner.len(), &get_scanner_relationships(parse_output));
 
     let mut max_manhatten = 0;
-    for s in &all_scanners_set {
-        for p in &all_scanners_set {
-            if s == p {
-                continue;
-            }
+    for s in &scanner_map {
+        for p in &scanner_map {
+            let (s, p) = (s.translation, p.translation);
             max_manhatten = max(
                 max_manhatten,
                 p[0].abs_diff(s[0]) + p[1].abs_diff(s[1]) + p[2].abs_diff(s[2]),
@@ -97,44 +62,75 @@ fn part_2(parse_output: &ParseOutput) -> Solution {
     max_manhatten
 }
 
-fn fill_beacon_set(
-    current_scanner: usize,
-    scanners: &Vec<Vec<Point>>,
-    path_map: &HashMap<usize, Vec<usize>>,
-    transformations: VecDeque<(Transformation, Point)>,
-    beacon_set: &mut HashSet<Point>,
-    scanner_relationships: &ScannerRelationships,
-    paths_traveled: &mut HashSet<(usize, usize)>,
-) {
-    for mut p in &scanners[current_scanner] {
-        let mut t_p = p.clone();
-        for (r, t) in &transformations {
-            t_p = translate_point(rotate_point(t_p, *r), *t);
+fn export_map(parse_output: &ParseOutput, directory: &Path) -> std::io::Result<()> {
+    let (scanner, _possible_rotations) = parse_output;
+
+    let scanner_relationships = get_scanner_relationships(parse_output);
+    let scanner_map = get_scanner_map(scanner.len(), &scanner_relationships);
+
+    export::write_all(
+        directory,
+        &get_beacons(scanner, &scanner_map),
+        &scanner_map,
+        &get_path_map(&scanner_relationships),
+    )
+}
+
+/// Every beacon in the coordinate system of scanner 0, sorted so exports are stable.
+fn get_beacons(scanner: &[Vec<Point>], scanner_map: &ScannerMap) -> Vec<Point> {
+    let mut all_beacons_set: HashSet<Point> = HashSet::new();
+    for (points, transform) in scanner.iter().zip(scanner_map) {
+        for p in points {
+            all_beacons_set.insert(transform.apply(*p));
         }
-        beacon_set.insert(t_p);
     }
 
-    for destination in path_map.get(&current_scanner).unwrap() {
-        if paths_traveled.contains(&(current_scanner, *destination)) {
-            continue;
-        }
+    let mut beacons: Vec<Point> = all_beacons_set.into_iter().collect();
+    beacons.sort();
+    beacons
+}
+
+fn get_path_map(scanner_relationships: &ScannerRelationships) -> PathMap {
+    let mut path_map: PathMap = HashMap::new();
+    for (base, other) in scanner_relationships.keys() {
+        path_map.entry(*base).or_default().push(*other);
+    }
+    for destinations in path_map.values_mut() {
+        destinations.sort();
+    }
+    path_map
+}
+
+/// Walks the overlap graph breadth first from scanner 0, composing the relationships
+/// along the way so every scanner ends up with one transform into scanner 0's frame.
+fn get_scanner_map(
+    scanner_count: usize,
+    scanner_relationships: &ScannerRelationships,
+) -> ScannerMap {
+    let path_map = get_path_map(scanner_relationships);
 
-        let t = scanner_relationships
-            .get(&(current_scanner, *destination))
-            .unwrap();
-        let mut trans = transformations.clone();
-        trans.push_front(t.clone());
-        paths_traveled.insert((current_scanner, *destination));
-        fill_beacon_set(
-            *destination,
-            scanners,
-            path_map,
-            trans,
-            beacon_set,
-            scanner_relationships,
-            paths_traveled,
-        )
+    let mut scanner_map: Vec<Option<RigidTransform>> = vec![None; scanner_count];
+    scanner_map[0] = Some(RigidTransform::IDENTITY);
+
+    let mut queue = VecDeque::from([0]);
+    while let Some(current_scanner) = queue.pop_front() {
+        let to_origin = scanner_map[current_scanner].unwrap();
+        for destination in path_map.get(&current_scanner).unwrap_or(&Vec::new()) {
+            if scanner_map[*destination].is_some() {
+                continue;
+            }
+            let relationship = scanner_relationships
+                .get(&(current_scanner, *destination))
+                .unwrap();
+            scanner_map[*destination] = Some(to_origin.compose(relationship));
+            queue.push_back(*destination);
+        }
     }
+
+    scanner_map
+        .into_iter()
+        .map(|t| t.expect("scanner does not overlap with any other scanner"))
+        .collect()
 }
 
 fn get_scanner_relationships(parse_output: &ParseOutput) -> ScannerRelationships {
@@ -145,11 +141,13 @@ fn get_scanner_relationships(parse_output: &ParseOutput) -> ScannerRelationships
         .map(|s| HashSet::from_iter(s.clone()))
         .collect();
 
+    let fingerprints: Vec<Fingerprint> = scanner.iter().map(|s| Fingerprint::new(s)).collect();
+
     let mut scanner_relationships = HashMap::new();
 
     for b in 0..scanner.len() {
         for o in 0..scanner.len() {
-            if b == o {
+            if b == o || !fingerprints[b].might_overlap(&fingerprints[o]) {
                 continue;
             }
             if let Some(transformation) = get_transformation(
@@ -157,6 +155,7 @@ fn get_scanner_relationships(parse_output: &ParseOutput) -> ScannerRelationships
                 &scanner[b],
                 &scanner[o],
                 &scanner_points_lookup[b],
+                &fingerprints[b].anchors(&fingerprints[o]),
             ) {
                 scanner_relationships.insert((b, o), transformation);
             }
@@ -168,41 +167,69 @@ fn get_scanner_relationships(parse_output: &ParseOutput) -> ScannerRelationships
 
 fn get_transformation(
     possible_rotations: &Transformations,
-    base_scanner: &Vec<Point>,
-    other_scanner: &Vec<Point>,
+    base_scanner: &[Point],
+    other_scanner: &[Point],
     base_scanner_hashset: &HashSet<Point>,
-) -> Option<(Transformation, Point)> {
-    for b in 0..base_scanner.len() - 12 {
-        for o in 0..base_scanner.len() - 12 {
-            if b == o {
+    anchors: &[(BeaconPair, BeaconPair)],
+) -> Option<RigidTransform> {
+    for ((b1, b2), (o1, o2)) in anchors {
+        let base_vector = minus(base_scanner[*b1], base_scanner[*b2]);
+
+        for tr in possible_rotations {
+            let p_o1 = tr.apply(other_scanner[*o1]);
+            let p_o2 = tr.apply(other_scanner[*o2]);
+
+            // The anchor pair doesn't tell us which end is which, so try both.
+            let other_point = if minus(p_o1, p_o2) == base_vector {
+                p_o1
+            } else if minus(p_o2, p_o1) == base_vector {
+                p_o2
+            } else {
                 continue;
             };
-            let base_point = &base_scanner[b];
-            let other_point = &base_scanner[o];
-
-            for tr in possible_rotations {
-                let mut distance_hashset = HashSet::<i32>::new();
-                for p_os in other_scanner {
-                    distance_hashset.insert(point_distance(*base_point, rotate_point(*p_os, *tr)));
-                }
+            let transform = RigidTransform::new(*tr, minus(other_point, base_scanner[*b1]));
+
+            let points_matching = other_scanner
+                .iter()
+                .filter(|p| base_scanner_hashset.contains(&transform.apply(**p)))
+                .count();
+            if points_matching >= MIN_OVERLAP {
+                return Some(transform);
+            }
+        }
+    }
+    get_transformation_exhaustive(
+        possible_rotations,
+        base_scanner,
+        other_scanner,
+        base_scanner_hashset,
+    )
+}
 
-                for p_os in other_scanner {
-                    let p_os_r = rotate_point(*p_os, *tr);
-                    let distance = point_distance(*other_point, p_os_r);
-                    if distance_hashset.contains(&distance) {
-                        let mut points_matching = 0;
-                        let translation = minus(p_os_r, *other_point);
-
-                        for p in other_scanner {
-                            let t_p = translate_point(rotate_point(*p, *tr), translation);
-                            if base_scanner_hashset.contains(&t_p) {
-                                points_matching += 1;
-                            }
-                        }
-                        if points_matching >= 12 {
-                            return Some((tr.clone(), translation));
-                        }
-                    }
+/// Tries every rotation and every way of putting a point of the other scanner on a base
+/// point. Used when the anchors don't lead to a transform, e.g. because the scanners share
+/// no distance that is unique on both sides. If `MIN_OVERLAP` points are shared, one of
+/// them is among the first `other_scanner.len() - MIN_OVERLAP + 1`, so only those are
+/// tried.
+fn get_transformation_exhaustive(
+    possible_rotations: &Transformations,
+    base_scanner: &[Point],
+    other_scanner: &[Point],
+    base_scanner_hashset: &HashSet<Point>,
+) -> Option<RigidTransform> {
+    for o in 0..other_scanner.len().saturating_sub(MIN_OVERLAP - 1) {
+        for tr in possible_rotations {
+            let other_point = tr.apply(other_scanner[o]);
+            for base_point in base_scanner {
+                let translation = minus(other_point, *base_point);
+                let points_matching = other_scanner
+                    .iter()
+                    .filter(|p| {
+                        base_scanner_hashset.contains(&translate_point(tr.apply(**p), translation))
+                    })
+                    .count();
+                if points_matching >= MIN_OVERLAP {
+                    return Some(RigidTransform::new(*tr, translation));
                 }
             }
         }
@@ -217,6 +244,33 @@ mod tests {
     use crate::parse_input::{parse, read_main, read_test};
     use test::{black_box, Bencher};
 
+    /// The search this solution started out with: every scanner pair, every pair of base
+    /// points and every rotation. Kept around to check and benchmark the fingerprint search.
+    fn get_scanner_relationships_exhaustive(parse_output: &ParseOutput) -> ScannerRelationships {
+        let (scanner, possible_rotations) = parse_output;
+
+        let mut scanner_relationships = HashMap::new();
+
+        for b in 0..scanner.len() {
+            let base_scanner_hashset: HashSet<Point> = HashSet::from_iter(scanner[b].clone());
+            for o in 0..scanner.len() {
+                if b == o {
+                    continue;
+                }
+                if let Some(transformation) = get_transformation_exhaustive(
+                    possible_rotations,
+                    &scanner[b],
+                    &scanner[o],
+                    &base_scanner_hashset,
+                ) {
+                    scanner_relationships.insert((b, o), transformation);
+                }
+            }
+        }
+
+        scanner_relationships
+    }
+
     #[test]
     pub fn test_part_1() {
         let parse_output = parse(&read_test());
@@ -228,6 +282,85 @@ mod tests {
         let parse_output = parse(&read_test());
         assert_eq!(part_2(&parse_output), 3621);
     }
+
+    #[test]
+    pub fn test_scanner_map() {
+        let parse_output = parse(&read_test());
+        let scanner_map = get_scanner_map(
+            parse_output.0.len(),
+            &get_scanner_relationships(&parse_output),
+        );
+
+        assert_eq!(scanner_map[0], RigidTransform::IDENTITY);
+        assert_eq!(scanner_map[1].translation, [68, -1246, -43]);
+        assert_eq!(scanner_map[2].translation, [1105, -1205, 1229]);
+        assert_eq!(scanner_map[3].translation, [-92, -2380, -20]);
+        assert_eq!(scanner_map[4].translation, [-20, -1133, 1061]);
+        assert_eq!(scanner_map[1].apply([686, 422, 578]), [-618, -824, -621]);
+    }
+
+    #[test]
+    pub fn test_fingerprint_search_finds_same_relationships() {
+        let parse_output = parse(&read_test());
+        let relationships = get_scanner_relationships(&parse_output);
+        let exhaustive = get_scanner_relationships_exhaustive(&parse_output);
+
+        let mut pairs: Vec<&(usize, usize)> = relationships.keys().collect();
+        let mut exhaustive_pairs: Vec<&(usize, usize)> = exhaustive.keys().collect();
+        pairs.sort();
+        exhaustive_pairs.sort();
+        assert_eq!(pairs, exhaustive_pairs);
+
+        for (pair, transform) in &relationships {
+            assert_eq!(transform, exhaustive.get(pair).unwrap());
+        }
+    }
+
+    #[test]
+    pub fn test_transformation_without_anchors() {
+        let (scanner, possible_rotations) = parse(&read_test());
+        let lookup: HashSet<Point> = HashSet::from_iter(scanner[0].clone());
+
+        let transform =
+            get_transformation(&possible_rotations, &scanner[0], &scanner[1], &lookup, &[])
+                .unwrap();
+        assert_eq!(transform.translation, [68, -1246, -43]);
+    }
+
+    #[test]
+    pub fn test_transformation_with_few_points() {
+        let (scanner, possible_rotations) = parse(&read_test());
+        let few: Vec<Point> = scanner[0][..5].to_vec();
+        let lookup: HashSet<Point> = HashSet::from_iter(few.clone());
+
+        assert_eq!(
+            get_transformation(&possible_rotations, &few, &scanner[1], &lookup, &[]),
+            None
+        );
+        assert_eq!(
+            get_transformation(&possible_rotations, &scanner[0], &few, &lookup, &[]),
+            None
+        );
+    }
+
+    #[bench]
+    fn bench_scanner_relationships(b: &mut Bencher) {
+        let parse_output = parse(&read_main());
+        b.iter(|| {
+            let _ = get_scanner_relationships(black_box(&parse_output));
+        });
+    }
+
+    /// Takes seconds per iteration, compare with `bench_scanner_relationships`. Ignored
+    /// because benches also run once under `cargo test`.
+    #[bench]
+    #[ignore]
+    fn bench_scanner_relationships_exhaustive(b: &mut Bencher) {
+        let parse_output = parse(&read_main());
+        b.iter(|| {
+            let _ = get_scanner_relationships_exhaustive(black_box(&parse_output));
+        });
+    }
     /*
     #[bench]
     fn bench_parse(b: &mut Bencher) {
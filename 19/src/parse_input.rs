@@ -1,7 +1,6 @@
-use crate::algebruh::{Point, Transformation};
-use std::collections::HashSet;
+use crate::algebruh::{Point, Rotation};
 
-pub type Transformations = [Transformation; 24];
+pub type Transformations = [Rotation; 24];
 pub type ParseOutput = (Vec<Vec<Point>>, Transformations);
 
 pub fn read_main() -> String {
@@ -49,59 +48,5 @@ pub fn parse(file: &String) -> ParseOutput {
 }
 
 fn get_rotations() -> Transformations {
-    /*
-    let mut hashb: HashSet<Transformation> = HashSet::new();
-    let mut t = (0, 1, 2, 1, 1, 1);
-    for _ in 0..4 {
-        t = rotate_around_x(t);
-        hashb.insert(t);
-        for _ in 0..4 {
-            t = rotate_around_y(t);
-            hashb.insert(t);
-            for _ in 0..4 {
-                t = rotate_around_z(t);
-                hashb.insert(t);
-            }
-        }
-    }
-    fn rotate_around_z(t: Transformation) -> Transformation {
-        (t.1, t.0, t.2, -t.4, t.3, t.5)
-    }
-
-    fn rotate_around_y(t: Transformation) -> Transformation {
-        (t.2, t.1, t.0, -t.5, t.4, t.3)
-    }
-
-    fn rotate_around_x(t: Transformation) -> Transformation {
-        (t.0, t.2, t.1, t.3, -t.5, t.4)
-    }
-
-
-    */
-    [
-        (2, 0, 1, -1, -1, 1),
-        (2, 0, 1, -1, 1, -1),
-        (1, 2, 0, -1, -1, 1),
-        (2, 1, 0, -1, 1, 1),
-        (0, 2, 1, -1, 1, 1),
-        (0, 1, 2, -1, -1, 1),
-        (2, 0, 1, 1, -1, -1),
-        (1, 2, 0, 1, -1, -1),
-        (1, 0, 2, -1, 1, 1),
-        (1, 0, 2, 1, 1, -1),
-        (0, 1, 2, -1, 1, -1),
-        (0, 2, 1, 1, 1, -1),
-        (1, 0, 2, -1, -1, -1),
-        (1, 2, 0, -1, 1, -1),
-        (0, 1, 2, 1, 1, 1),
-        (0, 1, 2, 1, -1, -1),
-        (2, 1, 0, 1, 1, -1),
-        (2, 0, 1, 1, 1, 1),
-        (1, 0, 2, 1, -1, 1),
-        (0, 2, 1, -1, -1, -1),
-        (2, 1, 0, 1, -1, 1),
-        (2, 1, 0, 1, -1, -1),
-        (1, 2, 0, 1, 1, 1),
-        (0, 2, 1, 1, -1, 1),
-    ]
+    Rotation::all()
 }
@@ -0,0 +1,245 @@
+use crate::game::{GameConfig, RollDistribution};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct GameState {
+    positions: Vec<u64>,
+    scores: Vec<u64>,
+    next_player: usize,
+}
+
+/// `wins` counts universes like the puzzle does. Games that take more turns split into
+/// more universes, so `win_probabilities` weighs every turn by the chance of its roll
+/// instead, which is what a player with a fair die would actually see.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+    pub wins: Vec<u128>,
+    pub win_probabilities: Vec<f64>,
+    /// Turns of all players together until somebody has won.
+    pub expected_turns: f64,
+}
+
+impl Outcome {
+    fn empty(player_count: usize) -> Outcome {
+        Outcome {
+            wins: vec![0; player_count],
+            win_probabilities: vec![0.0; player_count],
+            expected_turns: 0.0,
+        }
+    }
+
+    fn won_by(player: usize, player_count: usize) -> Outcome {
+        let mut outcome = Outcome::empty(player_count);
+        outcome.wins[player] = 1;
+        outcome.win_probabilities[player] = 1.0;
+        outcome
+    }
+}
+
+/// Plays every universe of a Dirac dice game, remembering the outcome of each state so
+/// that universes that reach the same positions and scores are only played once.
+pub struct DiracSolver<'a> {
+    config: &'a GameConfig,
+    roll_distribution: RollDistribution,
+    rolls_total: u64,
+    memo: HashMap<GameState, Outcome>,
+}
+
+impl<'a> DiracSolver<'a> {
+    pub fn new(config: &'a GameConfig) -> DiracSolver<'a> {
+        let roll_distribution = config.roll_distribution();
+        let rolls_total = roll_distribution.iter().map(|(_, f)| f).sum();
+        DiracSolver {
+            config,
+            roll_distribution,
+            rolls_total,
+            memo: HashMap::new(),
+        }
+    }
+
+    pub fn solve(&mut self, start_positions: &[u64]) -> Outcome {
+        assert_eq!(start_positions.len(), self.config.player_count);
+        self.analyse(&GameState {
+            positions: start_positions.to_vec(),
+            scores: vec![0; self.config.player_count],
+            next_player: 0,
+        })
+    }
+
+    fn analyse(&mut self, state: &GameState) -> Outcome {
+        let player_count = self.config.player_count;
+        let previous_player = (state.next_player + player_count - 1) % player_count;
+        if state.scores[previous_player] >= self.config.winning_score {
+            return Outcome::won_by(previous_player, player_count);
+        }
+        if let Some(outcome) = self.memo.get(state) {
+            return outcome.clone();
+        }
+
+        let mut outcome = Outcome::empty(player_count);
+        for (roll, frequency) in self.roll_distribution.clone() {
+            let player = state.next_player;
+            let mut next = state.clone();
+            next.positions[player] = self.config.move_pawn(state.positions[player], roll);
+            next.scores[player] += next.positions[player];
+            next.next_player = (player + 1) % player_count;
+
+            let sub_outcome = self.analyse(&next);
+            let probability = frequency as f64 / self.rolls_total as f64;
+            for p in 0..player_count {
+                outcome.wins[p] = sub_outcome.wins[p]
+                    .checked_mul(frequency as u128)
+                    .and_then(|w| w.checked_add(outcome.wins[p]))
+                    .expect("universe count does not fit into u128");
+                outcome.win_probabilities[p] += probability * sub_outcome.win_probabilities[p];
+            }
+            outcome.expected_turns += probability * (1.0 + sub_outcome.expected_turns);
+        }
+
+        self.memo.insert(state.clone(), outcome.clone());
+        outcome
+    }
+}
+
+/// Outcomes of a two player game for every pair of start positions, indexed by
+/// `[player 1 start - 1][player 2 start - 1]`.
+pub fn start_position_table(config: &GameConfig) -> Vec<Vec<Outcome>> {
+    assert_eq!(config.player_count, 2);
+    let mut solver = DiracSolver::new(config);
+    (1..=config.board_size)
+        .map(|p1| {
+            (1..=config.board_size)
+                .map(|p2| solver.solve(&[p1, p2]))
+                .collect()
+        })
+        .collect()
+}
+
+const HEAT: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Player 1's win probability per start position pair as a table of percentages and
+/// as a heatmap, player 1's start going down and player 2's start going right, followed
+/// by the universes won, the win probability of every player and the expected number of
+/// turns for each start position pair.
+pub fn render_table(table: &[Vec<Outcome>]) -> String {
+    let header: String = (1..=table.len()).map(|p2| format!("{:>7}", p2)).collect();
+    let mut rendered = format!("P1\\P2{}\n", header);
+    for (p1, row) in table.iter().enumerate() {
+        rendered.push_str(&format!("{:>5}", p1 + 1));
+        for outcome in row {
+            rendered.push_str(&format!("{:>6.1}%", outcome.win_probabilities[0] * 100.0));
+        }
+        rendered.push('\n');
+    }
+
+    rendered.push('\n');
+    for row in table {
+        let heat: String = row
+            .iter()
+            .map(|outcome| {
+                let level = (outcome.win_probabilities[0] * HEAT.len() as f64) as usize;
+                HEAT[level.min(HEAT.len() - 1)]
+            })
+            .collect();
+        rendered.push_str(&format!("{}\n", heat));
+    }
+
+    rendered.push('\n');
+    rendered.push_str(&render_details(table));
+    rendered
+}
+
+fn render_details(table: &[Vec<Outcome>]) -> String {
+    let player_count = table
+        .first()
+        .and_then(|row| row.first())
+        .map_or(0, |outcome| outcome.wins.len());
+
+    let mut rendered = String::from("   P1    P2");
+    for p in 1..=player_count {
+        rendered.push_str(&format!(
+            "{:>22}{:>9}",
+            format!("wins P{}", p),
+            format!("P{}", p)
+        ));
+    }
+    rendered.push_str(&format!("{:>9}\n", "turns"));
+
+    for (p1, row) in table.iter().enumerate() {
+        for (p2, outcome) in row.iter().enumerate() {
+            rendered.push_str(&format!("{:>5} {:>5}", p1 + 1, p2 + 1));
+            for (wins, probability) in outcome.wins.iter().zip(&outcome.win_probabilities) {
+                rendered.push_str(&format!("{:>22}{:>8.1}%", wins, probability * 100.0));
+            }
+            rendered.push_str(&format!("{:>9.2}\n", outcome.expected_turns));
+        }
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use crate::analysis::{render_table, start_position_table, DiracSolver};
+    use crate::game::GameConfig;
+
+    #[test]
+    pub fn test_solve() {
+        let config = GameConfig::dirac(2);
+        let outcome = DiracSolver::new(&config).solve(&[4, 8]);
+
+        assert_eq!(outcome.wins, vec![444356092776315, 341960390180808]);
+        assert!((outcome.win_probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(outcome.expected_turns > 6.0);
+    }
+
+    #[test]
+    pub fn test_solve_three_players() {
+        let config = GameConfig {
+            winning_score: 15,
+            ..GameConfig::dirac(3)
+        };
+        let outcome = DiracSolver::new(&config).solve(&[4, 8, 1]);
+
+        assert!((outcome.win_probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(outcome.wins.iter().all(|w| *w > 0));
+    }
+
+    #[test]
+    pub fn test_solve_deterministic_die() {
+        // With a one-sided die there is only a single universe: player 1 goes 7, 10, 3, 6
+        // (26) while player 2 goes 1, 4, 7 (12), seven turns in total.
+        let config = GameConfig {
+            die_sides: 1,
+            winning_score: 21,
+            ..GameConfig::dirac(2)
+        };
+        let outcome = DiracSolver::new(&config).solve(&[4, 8]);
+
+        assert_eq!(outcome.wins, vec![1, 0]);
+        assert_eq!(outcome.win_probabilities, vec![1.0, 0.0]);
+        assert_eq!(outcome.expected_turns, 7.0);
+    }
+
+    #[test]
+    pub fn test_render_table() {
+        let table = start_position_table(&GameConfig::dirac(2));
+        assert_eq!(table.len(), 10);
+        assert_eq!(table[3][7].wins, vec![444356092776315, 341960390180808]);
+
+        let rendered = render_table(&table);
+        assert_eq!(rendered.lines().count(), 1 + 10 + 1 + 10 + 1 + 1 + 100);
+        assert!(rendered.starts_with("P1\\P2      1      2"));
+
+        let details: Vec<&str> = rendered.lines().skip(23).collect();
+        assert_eq!(
+            details[0],
+            "   P1    P2               wins P1       P1               wins P2       P2    turns"
+        );
+        let line = details[1 + 3 * 10 + 7];
+        assert!(line.starts_with("    4     8       444356092776315"));
+        assert!(line.contains("       341960390180808"));
+        assert_eq!(line.matches('%').count(), 2);
+    }
+}
@@ -0,0 +1,92 @@
+/// Pairs of (sum of all rolls in one turn, number of ways to roll that sum).
+pub type RollDistribution = Vec<(u64, u64)>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameConfig {
+    pub board_size: u64,
+    pub die_sides: u64,
+    pub rolls_per_turn: u64,
+    pub winning_score: u64,
+    pub player_count: usize,
+}
+
+impl GameConfig {
+    /// The game from part 1, played with the deterministic 100-sided die.
+    pub fn deterministic(player_count: usize) -> GameConfig {
+        GameConfig {
+            board_size: 10,
+            die_sides: 100,
+            rolls_per_turn: 3,
+            winning_score: 1000,
+            player_count,
+        }
+    }
+
+    /// The game from part 2, played with the 3-sided Dirac die.
+    pub fn dirac(player_count: usize) -> GameConfig {
+        GameConfig {
+            board_size: 10,
+            die_sides: 3,
+            rolls_per_turn: 3,
+            winning_score: 21,
+            player_count,
+        }
+    }
+
+    /// Spaces are numbered from 1 to `board_size` and wrap around.
+    pub fn move_pawn(&self, position: u64, steps: u64) -> u64 {
+        ((position - 1 + steps) % self.board_size) + 1
+    }
+
+    /// How often each sum comes up when rolling the die `rolls_per_turn` times and
+    /// every roll splits the universe once per side.
+    pub fn roll_distribution(&self) -> RollDistribution {
+        let mut frequencies: Vec<u64> = vec![1];
+        for _ in 0..self.rolls_per_turn {
+            let mut next = vec![0; frequencies.len() + self.die_sides as usize];
+            for (sum, frequency) in frequencies.iter().enumerate() {
+                for side in 1..=self.die_sides as usize {
+                    next[sum + side] += frequency;
+                }
+            }
+            frequencies = next;
+        }
+
+        frequencies
+            .into_iter()
+            .enumerate()
+            .filter(|(_, frequency)| *frequency > 0)
+            .map(|(sum, frequency)| (sum as u64, frequency))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use crate::game::GameConfig;
+
+    #[test]
+    pub fn test_move_pawn() {
+        let config = GameConfig::dirac(2);
+        assert_eq!(config.move_pawn(4, 6), 10);
+        assert_eq!(config.move_pawn(4, 7), 1);
+        assert_eq!(config.move_pawn(10, 9), 9);
+    }
+
+    #[test]
+    pub fn test_roll_distribution() {
+        assert_eq!(
+            GameConfig::dirac(2).roll_distribution(),
+            vec![(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]
+        );
+
+        let config = GameConfig {
+            die_sides: 2,
+            rolls_per_turn: 2,
+            ..GameConfig::dirac(2)
+        };
+        assert_eq!(config.roll_distribution(), vec![(2, 1), (3, 2), (4, 1)]);
+    }
+}
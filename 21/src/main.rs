@@ -1,8 +1,11 @@
 #![feature(test)]
 
+use crate::analysis::{render_table, start_position_table, DiracSolver};
+use crate::game::GameConfig;
 use crate::parse_input::{parse, read_main, ParseOutput};
-use std::cmp::max;
 
+pub mod analysis;
+pub mod game;
 pub mod parse_input;
 
 type Solution = u64;
@@ -11,143 +14,58 @@ fn main() {
     let parse_output = parse(&read_main());
     println!("Solution to part 1 is {}", part_1(&parse_output));
     println!("Solution to part 2 is {}", part_2(&parse_output));
+
+    // `cargo run -- table` also shows how the start positions affect every player's chances.
+    if std::env::args().nth(1).as_deref() == Some("table") {
+        println!(
+            "{}",
+            render_table(&start_position_table(&GameConfig::dirac(2)))
+        );
+    }
 }
 
 fn part_1(parse_output: &ParseOutput) -> Solution {
-    let (mut p1_pos, mut p2_pos) = parse_output.clone();
-    let mut p1_score = 0;
-    let mut p2_score = 0;
-    let mut dice: i32 = 1;
-
-    loop {
-        advance_player(&mut p1_pos, &mut p1_score, &mut dice);
+    play_deterministic(&GameConfig::deterministic(parse_output.len()), parse_output)
+}
 
-        if p1_score >= 1000 {
-            return ((dice - 1) * p2_score) as u64;
-        }
+/// Number of die rolls times the lowest score once somebody has won.
+fn play_deterministic(config: &GameConfig, start_positions: &[u64]) -> Solution {
+    assert_eq!(start_positions.len(), config.player_count);
+    let mut positions = start_positions.to_vec();
+    let mut scores = vec![0; config.player_count];
+    let mut dice: u64 = 1;
 
-        advance_player(&mut p2_pos, &mut p2_score, &mut dice);
+    loop {
+        for p in 0..config.player_count {
+            advance_player(config, &mut positions[p], &mut scores[p], &mut dice);
 
-        if p2_score >= 1000 {
-            return ((dice - 1) * p1_score) as u64;
+            if scores[p] >= config.winning_score {
+                return (dice - 1) * scores.iter().min().unwrap();
+            }
         }
     }
 }
 
-fn advance_player(p_pos: &mut i32, p_score: &mut i32, dice: &mut i32) {
-    *p_pos += get_dice_roll_value(3, dice);
-    *p_pos = ((*p_pos - 1) % 10) + 1;
+fn advance_player(config: &GameConfig, p_pos: &mut u64, p_score: &mut u64, dice: &mut u64) {
+    *p_pos = config.move_pawn(*p_pos, get_dice_roll_value(config, dice));
     *p_score += *p_pos;
 }
 
-fn get_dice_roll_value(times: i32, amount_of_dice_roll: &mut i32) -> i32 {
+fn get_dice_roll_value(config: &GameConfig, amount_of_dice_roll: &mut u64) -> u64 {
     let mut value = 0;
-    for _ in 0..times {
-        value += ((*amount_of_dice_roll - 1) % 100) + 1;
+    for _ in 0..config.rolls_per_turn {
+        value += ((*amount_of_dice_roll - 1) % config.die_sides) + 1;
         *amount_of_dice_roll += 1;
     }
     value
 }
 
 fn part_2(parse_output: &ParseOutput) -> Solution {
-    let (p1, p2) = parse_output.clone();
-    let possible_next_field_values = get_possible_next_field_values();
-    let possible_dice_value_frequency = [1, 3, 6, 7, 6, 3, 1]; // 3x1..3 -> 1x3, 3x4, 6x5, 7x6, 6x7, 3x8, 1x9
-
-    let p1_start = p1 as u64;
-    let p2_start = p2 as u64;
-    let mut p1_won: u64 = 0;
-    let mut p2_won: u64 = 0;
-    traverse_options(
-        p1_start,
-        0,
-        p2_start,
-        0,
-        1,
-        &mut p1_won,
-        &mut p2_won,
-        true,
-        &possible_next_field_values,
-        &possible_dice_value_frequency,
-    );
-
-    max(p1_won, p2_won)
-}
+    let config = GameConfig::dirac(parse_output.len());
+    let outcome = DiracSolver::new(&config).solve(parse_output);
 
-fn traverse_options(
-    p1_pos: u64,
-    p1_val: u64,
-    p2_pos: u64,
-    p2_val: u64,
-    possible_paths: u64,
-    won_p1: &mut u64,
-    won_p2: &mut u64,
-    next_player_is_1: bool,
-    possible_next_values_map: &[[u64; 7]; 10],
-    possible_dice_value_frequency: &[u64; 7],
-) {
-    if next_player_is_1 {
-        if p2_val >= 21 {
-            *won_p2 += possible_paths;
-            return;
-        }
-        let possible_next_values = possible_next_values_map[p1_pos as usize - 1];
-        for i in 0..7 {
-            let next_pos = &possible_next_values[i];
-            let next_pos_freq = &possible_dice_value_frequency[i];
-
-            traverse_options(
-                *next_pos,
-                p1_val + *next_pos,
-                p2_pos,
-                p2_val,
-                possible_paths * *next_pos_freq,
-                won_p1,
-                won_p2,
-                false,
-                possible_next_values_map,
-                possible_dice_value_frequency,
-            );
-        }
-    } else {
-        if p1_val >= 21 {
-            *won_p1 += possible_paths;
-            return;
-        }
-        let possible_next_values = possible_next_values_map[p2_pos as usize - 1];
-        for i in 0..7 {
-            let next_pos = &possible_next_values[i];
-            let next_pos_freq = &possible_dice_value_frequency[i];
-
-            traverse_options(
-                p1_pos,
-                p1_val,
-                *next_pos,
-                p2_val + *next_pos,
-                possible_paths * *next_pos_freq,
-                won_p1,
-                won_p2,
-                true,
-                possible_next_values_map,
-                possible_dice_value_frequency,
-            );
-        }
-    }
-}
-
-fn get_possible_next_field_values() -> [[u64; 7]; 10] {
-    [
-        [4, 5, 6, 7, 8, 9, 10], // Start pos 1
-        [5, 6, 7, 8, 9, 10, 1], // Start pos 2
-        [6, 7, 8, 9, 10, 1, 2], // Start pos 3
-        [7, 8, 9, 10, 1, 2, 3], // Start pos 4
-        [8, 9, 10, 1, 2, 3, 4], // Start pos 5
-        [9, 10, 1, 2, 3, 4, 5], // Start pos 6
-        [10, 1, 2, 3, 4, 5, 6], // Start pos 7
-        [1, 2, 3, 4, 5, 6, 7],  // Start pos 8
-        [2, 3, 4, 5, 6, 7, 8],  // Start pos 9
-        [3, 4, 5, 6, 7, 8, 9],  // Start pos 10
-    ]
+    Solution::try_from(*outcome.wins.iter().max().unwrap())
+        .expect("universe count does not fit into the solution type")
 }
 
 #[cfg(test)]
@@ -170,6 +88,22 @@ mod tests {
         assert_eq!(part_2(&parse_output), 444356092776315);
     }
 
+    #[test]
+    pub fn test_play_deterministic_with_other_rules() {
+        let config = GameConfig {
+            winning_score: 20,
+            ..GameConfig::deterministic(2)
+        };
+        // 4 -> 10 (10), 8 -> 3 (3), 10 -> 4 (14), 3 -> 6 (9), 4 -> 6 (20)
+        assert_eq!(play_deterministic(&config, &[4, 8]), 15 * 9);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_play_deterministic_checks_player_count() {
+        play_deterministic(&GameConfig::deterministic(3), &[4, 8]);
+    }
+
     #[bench]
     fn bench_parse(b: &mut Bencher) {
         let file = read_main();
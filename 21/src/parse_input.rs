@@ -1,4 +1,5 @@
-pub type ParseOutput = (i32, i32);
+/// Starting position of every player, in turn order.
+pub type ParseOutput = Vec<u64>;
 
 pub fn read_main() -> String {
     read_file("src/21.txt")
@@ -13,11 +14,15 @@ pub fn read_file(file_name: &str) -> String {
 }
 
 pub fn parse(file: &String) -> ParseOutput {
-    let (p1, p2) = file.split_once("Player 2 starting position:").unwrap();
-    (
-        p1.trim()["Player 1 starting position: ".len()..]
-            .parse()
-            .unwrap(),
-        p2.trim().parse().unwrap(),
-    )
+    file.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            l.split_once("starting position:")
+                .unwrap()
+                .1
+                .trim()
+                .parse()
+                .unwrap()
+        })
+        .collect()
 }
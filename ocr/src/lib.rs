@@ -0,0 +1,383 @@
+//! Reads the block letters Advent of Code puzzles draw with dots, like the folded paper of
+//! day 13 or the message in the stars of 2018 day 10.
+
+use std::collections::HashSet;
+use std::fmt;
+
+/// A fixed width font. Glyphs follow each other every `pitch` columns. Most are `width`
+/// columns wide, so there are `pitch - width` empty columns between them, but some are
+/// wider and leave less of a gap, up to none at all.
+pub struct Font {
+    pub width: usize,
+    pub height: usize,
+    pub pitch: usize,
+    glyphs: &'static [(char, &'static str)],
+}
+
+/// The letters most puzzles use, 4 columns by 6 rows.
+pub const SMALL_FONT: Font = Font {
+    width: 4,
+    height: 6,
+    pitch: 5,
+    glyphs: &[
+        ('A', ".##.#..##..######..##..#"),
+        ('B', "###.#..####.#..##..####."),
+        ('C', ".##.#..##...#...#..#.##."),
+        ('E', "#####...###.#...#...####"),
+        ('F', "#####...###.#...#...#..."),
+        ('G', ".##.#..##...#.###..#.###"),
+        ('H', "#..##..######..##..##..#"),
+        ('I', ".###..#...#...#...#..###"),
+        ('J', "..##...#...#...##..#.##."),
+        ('K', "#..##.#.##..#.#.#.#.#..#"),
+        ('L', "#...#...#...#...#...####"),
+        ('O', ".##.#..##..##..##..#.##."),
+        ('P', "###.#..##..####.#...#..."),
+        ('R', "###.#..##..####.#.#.#..#"),
+        ('S', ".####...#....##....####."),
+        ('U', "#..##..##..##..##..#.##."),
+        ('Y', "#...##...#.#.#...#....#....#.."),
+        ('Z', "####...#..#..#..#...####"),
+    ],
+};
+
+/// The taller letters, 6 columns by 10 rows.
+pub const LARGE_FONT: Font = Font {
+    width: 6,
+    height: 10,
+    pitch: 8,
+    glyphs: &[
+        (
+            'A',
+            "..##...#..#.#....##....##....########....##....##....##....#",
+        ),
+        (
+            'B',
+            "#####.#....##....##....######.#....##....##....##....######.",
+        ),
+        (
+            'C',
+            ".####.#....##.....#.....#.....#.....#.....#.....#....#.####.",
+        ),
+        (
+            'E',
+            "#######.....#.....#.....#####.#.....#.....#.....#.....######",
+        ),
+        (
+            'F',
+            "#######.....#.....#.....#####.#.....#.....#.....#.....#.....",
+        ),
+        (
+            'G',
+            ".####.#....##.....#.....#.....#..####....##....##...##.###.#",
+        ),
+        (
+            'H',
+            "#....##....##....##....########....##....##....##....##....#",
+        ),
+        (
+            'J',
+            "...###....#.....#.....#.....#.....#.....#.#...#.#...#..###..",
+        ),
+        (
+            'K',
+            "#....##...#.#..#..#.#...##....##....#.#...#..#..#...#.#....#",
+        ),
+        (
+            'L',
+            "#.....#.....#.....#.....#.....#.....#.....#.....#.....######",
+        ),
+        (
+            'N',
+            "#....###...###...##.#..##.#..##..#.##..#.##...###...###....#",
+        ),
+        (
+            'P',
+            "#####.#....##....##....######.#.....#.....#.....#.....#.....",
+        ),
+        (
+            'R',
+            "#####.#....##....##....######.#..#..#...#.#...#.#....##....#",
+        ),
+        (
+            'X',
+            "#....##....#.#..#..#..#...##....##...#..#..#..#.#....##....#",
+        ),
+        (
+            'Z',
+            "######.....#.....#....#....#....#....#....#.....#.....######",
+        ),
+    ],
+};
+
+/// What can't be read. `text` has a `?` for every glyph that isn't a letter of the font.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OcrError {
+    /// The dots are as high as no font.
+    UnknownHeight(usize),
+    /// The first column of every glyph that isn't a letter of the font.
+    Unrecognised { columns: Vec<usize>, text: String },
+    /// The first row that isn't as long as the ones before it.
+    RaggedRows(usize),
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::UnknownHeight(height) => write!(f, "no font is {} rows high", height),
+            OcrError::Unrecognised { columns, text } => write!(
+                f,
+                "can't read the glyphs at columns {:?}, got `{}`",
+                columns, text
+            ),
+            OcrError::RaggedRows(row) => {
+                write!(f, "row {} isn't as long as the rows before it", row)
+            }
+        }
+    }
+}
+
+impl Font {
+    /// `cell` is a whole cell of the font, the glyph and the gap after it. Only the gap
+    /// a glyph leaves has to be empty.
+    fn find_letter(&self, cell: &str) -> Option<char> {
+        self.glyphs
+            .iter()
+            .find(|(_, pattern)| {
+                let width = pattern.len() / self.height;
+                (0..self.height).all(|y| {
+                    let row = &cell[y * self.pitch..(y + 1) * self.pitch];
+                    row[..width] == pattern[y * width..(y + 1) * width]
+                        && !row[width..].contains('#')
+                })
+            })
+            .map(|(letter, _)| *letter)
+    }
+
+    /// The cell starting at `left` as a string of its rows, `#` for dots.
+    fn get_cell(&self, grid: &[Vec<bool>], left: usize) -> String {
+        let mut cell = String::new();
+        for row in grid {
+            for x in left..left + self.pitch {
+                cell.push(if *row.get(x).unwrap_or(&false) {
+                    '#'
+                } else {
+                    '.'
+                });
+            }
+        }
+        cell
+    }
+
+    /// Reads a grid of the font's height, its first column being the first column of the
+    /// first glyph.
+    pub fn read_grid(&self, grid: &[Vec<bool>]) -> Result<String, OcrError> {
+        if grid.len() != self.height {
+            return Err(OcrError::UnknownHeight(grid.len()));
+        }
+        let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+
+        let mut text = String::new();
+        let mut columns = Vec::new();
+        for left in (0..width).step_by(self.pitch) {
+            match self.find_letter(&self.get_cell(grid, left)) {
+                Some(letter) => text.push(letter),
+                None => {
+                    text.push('?');
+                    columns.push(left);
+                }
+            }
+        }
+
+        match columns.len() {
+            0 => Ok(text),
+            _ => Err(OcrError::Unrecognised { columns, text }),
+        }
+    }
+}
+
+/// Reads a grid of dots, each row the same length, picking the font by its height.
+///
+/// The grid may start a few columns early or late, as happens when it is cut down to the
+/// dots and the first letter starts with an empty column. Every start up to the pitch of
+/// the font is tried and the one reading the most letters wins.
+pub fn read_grid(grid: &[Vec<bool>]) -> Result<String, OcrError> {
+    if let Some(row) = grid.iter().position(|row| row.len() != grid[0].len()) {
+        return Err(OcrError::RaggedRows(row));
+    }
+    let rows: Vec<&Vec<bool>> = grid.iter().skip_while(|row| !row.contains(&true)).collect();
+    let height = rows
+        .iter()
+        .rposition(|row| row.contains(&true))
+        .map_or(0, |i| i + 1);
+    let rows = &rows[..height];
+
+    let font = [SMALL_FONT, LARGE_FONT]
+        .into_iter()
+        .find(|font| font.height == height)
+        .ok_or(OcrError::UnknownHeight(height))?;
+
+    let left = rows
+        .iter()
+        .filter_map(|row| row.iter().position(|dot| *dot))
+        .min()
+        .unwrap_or(0);
+
+    (0..font.pitch - font.width + 1)
+        .map(|shift| {
+            let shifted: Vec<Vec<bool>> = rows
+                .iter()
+                .map(|row| {
+                    let mut shifted = vec![false; shift];
+                    shifted.extend_from_slice(&row[left..]);
+                    shifted
+                })
+                .collect();
+            font.read_grid(&shifted)
+        })
+        .min_by_key(|result| match result {
+            Ok(_) => 0,
+            Err(OcrError::Unrecognised { columns, .. }) => columns.len(),
+            Err(_) => usize::MAX,
+        })
+        .unwrap()
+}
+
+/// Reads dots given as `(x, y)`.
+pub fn read_points<'a>(
+    points: impl IntoIterator<Item = &'a (i32, i32)>,
+) -> Result<String, OcrError> {
+    let points: HashSet<(i32, i32)> = points.into_iter().cloned().collect();
+    let (min_x, min_y) = points
+        .iter()
+        .fold((i32::MAX, i32::MAX), |(x, y), p| (x.min(p.0), y.min(p.1)));
+    let (max_x, max_y) = points
+        .iter()
+        .fold((i32::MIN, i32::MIN), |(x, y), p| (x.max(p.0), y.max(p.1)));
+    if points.is_empty() {
+        return Err(OcrError::UnknownHeight(0));
+    }
+
+    let grid: Vec<Vec<bool>> = (min_y..=max_y)
+        .map(|y| (min_x..=max_x).map(|x| points.contains(&(x, y))).collect())
+        .collect();
+    read_grid(&grid)
+}
+
+/// Reads dots drawn as text, one line per row and `lit` for a dot.
+pub fn read_text(text: &str, lit: char) -> Result<String, OcrError> {
+    let lines: Vec<&str> = text.lines().collect();
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let grid: Vec<Vec<bool>> = lines
+        .iter()
+        .map(|line| {
+            let mut row: Vec<bool> = line.chars().map(|c| c == lit).collect();
+            row.resize(width, false);
+            row
+        })
+        .collect();
+    read_grid(&grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(font: &Font, text: &str) -> String {
+        let mut rows = vec![String::new(); font.height];
+        for (i, letter) in text.chars().enumerate() {
+            let (_, glyph) = font.glyphs.iter().find(|(l, _)| *l == letter).unwrap();
+            let width = glyph.len() / font.height;
+            for (y, row) in rows.iter_mut().enumerate() {
+                if i > 0 {
+                    row.push_str(&".".repeat((font.pitch - row.len() % font.pitch) % font.pitch));
+                }
+                row.push_str(&glyph[y * width..(y + 1) * width]);
+            }
+        }
+        rows.join("\n")
+    }
+
+    #[test]
+    fn fonts_test() {
+        for font in [SMALL_FONT, LARGE_FONT] {
+            let mut seen = HashSet::new();
+            for (_, glyph) in font.glyphs {
+                assert_eq!(glyph.len() % font.height, 0);
+                let width = glyph.len() / font.height;
+                assert!(width >= font.width && width <= font.pitch);
+                assert!(seen.insert(glyph));
+            }
+        }
+    }
+
+    #[test]
+    fn read_small_font_test() {
+        let text: String = SMALL_FONT.glyphs.iter().map(|(l, _)| *l).collect();
+        assert_eq!(read_text(&render(&SMALL_FONT, &text), '#'), Ok(text));
+    }
+
+    #[test]
+    fn read_large_font_test() {
+        let text: String = LARGE_FONT.glyphs.iter().map(|(l, _)| *l).collect();
+        assert_eq!(read_text(&render(&LARGE_FONT, &text), '#'), Ok(text));
+    }
+
+    #[test]
+    fn read_wide_glyph_test() {
+        // Y takes up the gap after it, so the next letter follows right away.
+        let drawing = render(&SMALL_FONT, "YAY");
+        assert_eq!(drawing.lines().next(), Some("#...#.##..#...#"));
+        assert_eq!(read_text(&drawing, '#'), Ok("YAY".to_string()));
+        assert_eq!(
+            read_text(&render(&SMALL_FONT, "KY"), '#'),
+            Ok("KY".to_string())
+        );
+    }
+
+    #[test]
+    fn read_shifted_test() {
+        // I starts with an empty column, which cutting the grid down to the dots drops.
+        let drawing = render(&SMALL_FONT, "IJL");
+        let points: Vec<(i32, i32)> = drawing
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '#')
+                    .map(move |(x, _)| (x as i32 + 30, y as i32 - 4))
+            })
+            .collect();
+        assert_eq!(read_points(&points), Ok("IJL".to_string()));
+    }
+
+    #[test]
+    fn unrecognised_test() {
+        let mut drawing = render(&SMALL_FONT, "AB");
+        drawing.replace_range(0..1, "#");
+        assert_eq!(
+            read_text(&drawing, '#'),
+            Err(OcrError::Unrecognised {
+                columns: vec![0],
+                text: "?B".to_string()
+            })
+        );
+        assert_eq!(read_text("#\n#\n#", '#'), Err(OcrError::UnknownHeight(3)));
+    }
+
+    #[test]
+    fn ragged_rows_test() {
+        let mut grid: Vec<Vec<bool>> = render(&SMALL_FONT, "HI")
+            .lines()
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect();
+        assert_eq!(read_grid(&grid), Ok("HI".to_string()));
+        grid[2].truncate(3);
+        assert_eq!(read_grid(&grid), Err(OcrError::RaggedRows(2)));
+    }
+}